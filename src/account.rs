@@ -0,0 +1,263 @@
+use regex::Regex;
+use std::{collections::HashMap, fmt, fs::read_to_string};
+
+use crate::{config::Config, input::{get_client, read_file, DAYS, URL}, puzzle::{decode_entities, get_answers}};
+
+const USER_START: &str = "<div class=\"user\">";
+const STAR_COUNT_START: &str = "<span class=\"star-count\">";
+const LOGIN_LINK: &str = "/auth/login";
+
+const SOLUTIONS_DIR: &str = "./src/solutions";
+
+#[derive(Debug, PartialEq)]
+pub struct User {
+    name: String,
+    stars: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Session {
+    Valid(User),
+    Expired,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Part {
+    Missing,
+    Todo,
+    Implemented,
+    /// Upstream accepted an answer, as recorded in the cached puzzle description. This is the
+    /// star only, the local solver's output isn't checked against it.
+    Starred,
+}
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match self {
+            Part::Missing => "missing",
+            Part::Todo => "todo",
+            Part::Implemented => "implemented",
+            Part::Starred => "starred",
+        };
+        f.pad(status)
+    }
+}
+
+/// Reports who the session cookie belongs to, or that it is no longer valid.
+pub fn whoami(config: &Config) {
+    let html = fetch_event_page(config);
+
+    match get_session(&html) {
+        Session::Valid(User { name, stars }) => {
+            println!("logged in as {name} with {stars} stars in {}", config.year);
+        },
+        Session::Expired => {
+            println!("session cookie is invalid or has expired, log in again and update SESSION_COOKIE");
+        },
+    }
+}
+
+/// Prints a calendar comparing the stars earned upstream with the state of local solutions.
+pub fn progress(config: &Config) {
+    let html = fetch_event_page(config);
+
+    if get_session(&html) == Session::Expired {
+        panic!("session cookie is invalid or has expired");
+    }
+
+    let stars = get_stars(&html);
+
+    println!("day  stars  part one     part two");
+
+    for day in 1..=DAYS {
+        let file_name = format!("day_{day:02}");
+
+        let source = read_to_string(format!("{SOLUTIONS_DIR}/{file_name}.rs")).ok();
+        let description = read_file(&format!("{file_name}.html"), config);
+        let parts = get_parts(source.as_deref(), description.as_deref());
+
+        let stars = stars.get(&day).copied().unwrap_or(0);
+        let is_behind = parts
+            .iter()
+            .take(stars)
+            .any(|part| matches!(part, Part::Missing | Part::Todo));
+
+        println!(
+            "{day:>3}  {:<5}  {:<11}  {:<11}{}",
+            "*".repeat(stars),
+            parts[0],
+            parts[1],
+            if is_behind { "  starred upstream but not solved locally" } else { "" },
+        );
+    }
+}
+
+fn fetch_event_page(config: &Config) -> String {
+    let url = format!("{URL}/{}", config.year);
+
+    get_client(config)
+        .get(url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .unwrap_or_else(|err| panic!("failed to fetch event page: {err}"))
+}
+
+/// Stars per day, read from the calendar links' labels such as `Day 4, two stars`.
+fn get_stars(html: &str) -> HashMap<usize, usize> {
+    let label = Regex::new(r#"aria-label="Day (\d+)(?:, (one star|two stars))?""#).unwrap();
+
+    label
+        .captures_iter(html)
+        .map(|captures| {
+            let day = captures[1].parse().unwrap();
+            let stars = match captures.get(2).map(|stars| stars.as_str()) {
+                Some("one star") => 1,
+                Some("two stars") => 2,
+                _ => 0,
+            };
+            (day, stars)
+        })
+        .collect()
+}
+
+fn get_parts(source: Option<&str>, description: Option<&str>) -> [Part; 2] {
+    let Some(source) = source else {
+        return [Part::Missing, Part::Missing];
+    };
+
+    let answers = description.map(|html| get_answers(html).len()).unwrap_or(0);
+
+    ["solve_part_one", "solve_part_two"]
+        .into_iter()
+        .enumerate()
+        .map(|(index, function)| match get_body(source, function) {
+            None => Part::Missing,
+            Some(body) if body.contains("todo!(") => Part::Todo,
+            Some(_) if answers > index => Part::Starred,
+            Some(_) => Part::Implemented,
+        })
+        .collect::<Vec<Part>>()
+        .try_into()
+        .unwrap()
+}
+
+/// The source of a top-level function, up to the closing brace at the start of a line.
+fn get_body<'a>(source: &'a str, function: &str) -> Option<&'a str> {
+    let start = source.find(&format!("pub fn {function}("))?;
+    let body = &source[start..];
+    let end = body.find("\n}").unwrap_or(body.len());
+    Some(&body[..end])
+}
+
+pub fn get_session(html: &str) -> Session {
+    match get_user(html) {
+        Some(user) => Session::Valid(user),
+        None => Session::Expired,
+    }
+}
+
+fn get_user(html: &str) -> Option<User> {
+    let (_before, user) = html.split_once(USER_START)?;
+    let (user, _after) = user.split_once("</div>")?;
+
+    if user.contains(LOGIN_LINK) {
+        return None;
+    }
+
+    let (name, stars) = match user.split_once(STAR_COUNT_START) {
+        Some((name, star_count)) => {
+            let stars = star_count
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse()
+                .ok()?;
+            (name, stars)
+        },
+        None => (user, 0),
+    };
+
+    let name = decode_entities(name.split('<').next()?.trim());
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(User { name, stars })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGGED_IN: &str = "\
+        <header><div><h1 class=\"title-global\"><a href=\"/\">Advent of Code</a></h1>\
+        <nav><ul><li><a href=\"/2024/about\">[About]</a></li></ul></nav>\
+        <div class=\"user\">Ben Wormald <span class=\"star-count\">34*</span></div></div></header>\n\
+    ";
+
+    const NO_STARS: &str = "<div class=\"user\">(anonymous user #123456)</div>";
+
+    const LOGGED_OUT: &str = "\
+        <header><div><h1 class=\"title-global\"><a href=\"/\">Advent of Code</a></h1>\
+        <nav><ul><li><a href=\"/2024/auth/login\">[Log In]</a></li></ul></nav></div></header>\n\
+    ";
+
+    #[test]
+    fn logged_in() {
+        let expected = Session::Valid(User { name: String::from("Ben Wormald"), stars: 34 });
+
+        assert_eq!(get_session(LOGGED_IN), expected);
+    }
+
+    #[test]
+    fn no_stars() {
+        let expected = Session::Valid(User { name: String::from("(anonymous user #123456)"), stars: 0 });
+
+        assert_eq!(get_session(NO_STARS), expected);
+    }
+
+    #[test]
+    fn logged_out() {
+        assert_eq!(get_session(LOGGED_OUT), Session::Expired);
+    }
+
+    const CALENDAR: &str = "\
+        <pre class=\"calendar\">\
+        <a aria-label=\"Day 1, two stars\" href=\"/2024/day/1\" class=\"calendar-day1 calendar-verycomplete\">\
+        <span class=\"calendar-day\"> 1</span> <span class=\"calendar-mark-complete\">*</span></a>\
+        <a aria-label=\"Day 2, one star\" href=\"/2024/day/2\" class=\"calendar-day2 calendar-complete\">\
+        <span class=\"calendar-day\"> 2</span></a>\
+        <a aria-label=\"Day 3\" href=\"/2024/day/3\" class=\"calendar-day3\"><span class=\"calendar-day\"> 3</span></a>\
+        </pre>\
+    ";
+
+    #[test]
+    fn stars() {
+        let stars = get_stars(CALENDAR);
+
+        assert_eq!(stars.get(&1), Some(&2));
+        assert_eq!(stars.get(&2), Some(&1));
+        assert_eq!(stars.get(&3), Some(&0));
+        assert_eq!(stars.get(&4), None);
+    }
+
+    const SOURCE: &str = "\
+        pub fn solve_part_one(input: &str) -> usize {\n\
+        \x20   input.len()\n\
+        }\n\
+        \n\
+        pub fn solve_part_two(input: &str) -> usize {\n\
+        \x20   todo!()\n\
+        }\n\
+    ";
+
+    #[test]
+    fn parts() {
+        let description = "<p>Your puzzle answer was <code>11</code>.</p>";
+
+        assert_eq!(get_parts(None, None), [Part::Missing, Part::Missing]);
+        assert_eq!(get_parts(Some(SOURCE), None), [Part::Implemented, Part::Todo]);
+        assert_eq!(get_parts(Some(SOURCE), Some(description)), [Part::Starred, Part::Todo]);
+    }
+}
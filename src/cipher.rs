@@ -0,0 +1,113 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{aead::{Aead, OsRng}, ChaCha20Poly1305, Key, KeyInit, Nonce};
+use sha2::{Digest, Sha256};
+
+const HEADER: &str = "aoc-encrypted:v1:";
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+pub fn is_encrypted(contents: &str) -> bool {
+    contents.starts_with(HEADER)
+}
+
+/// A fresh random key, base64-encoded for use as the input key.
+pub fn generate_key() -> String {
+    STANDARD.encode(ChaCha20Poly1305::generate_key(&mut OsRng))
+}
+
+/// The nonce is derived from the key and the plaintext, so re-encrypting an unchanged input gives
+/// an identical file and no spurious diff.
+pub fn encrypt(plaintext: &str, key: &str) -> String {
+    let key = get_key(key);
+
+    let nonce_hash = Sha256::new()
+        .chain_update(key)
+        .chain_update(plaintext)
+        .finalize();
+    let nonce = Nonce::from_slice(&nonce_hash[..NONCE_LENGTH]);
+
+    let ciphertext = ChaCha20Poly1305::new(&key)
+        .encrypt(nonce, plaintext.as_bytes())
+        .expect("failed to encrypt input");
+
+    let mut payload = nonce.to_vec();
+    payload.extend(ciphertext);
+
+    format!("{HEADER}{}\n", STANDARD.encode(payload))
+}
+
+/// Returns `None` if the contents are malformed or the key is wrong, but panics if the key is not
+/// a valid input key at all.
+pub fn decrypt(contents: &str, key: &str) -> Option<String> {
+    let payload = contents.strip_prefix(HEADER)?.trim();
+    let payload = STANDARD.decode(payload).ok()?;
+
+    if payload.len() < NONCE_LENGTH {
+        return None;
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+
+    let plaintext = ChaCha20Poly1305::new(&get_key(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()?;
+
+    String::from_utf8(plaintext).ok()
+}
+
+/// Encrypted inputs are meant to be committed publicly, so the key must be random bytes rather
+/// than a passphrase that could be guessed offline.
+fn get_key(key: &str) -> Key {
+    STANDARD
+        .decode(key.trim())
+        .ok()
+        .filter(|bytes| bytes.len() == KEY_LENGTH)
+        .map(|bytes| *Key::from_slice(&bytes))
+        .unwrap_or_else(|| panic!(
+            "input key must be {KEY_LENGTH} random bytes in base64, generate one with `config generate-key`"
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "3   4\n4   3\n";
+    const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+    const OTHER: &str = "Hx4dHBsaGRgXFhUUExIREA8ODQwLCgkIBwYFBAMCAQA=";
+
+    #[test]
+    fn round_trip() {
+        let encrypted = encrypt(INPUT, KEY);
+
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt(&encrypted, KEY).as_deref(), Some(INPUT));
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(encrypt(INPUT, KEY), encrypt(INPUT, KEY));
+        assert_ne!(encrypt(INPUT, KEY), encrypt(INPUT, OTHER));
+    }
+
+    #[test]
+    fn generated() {
+        let key = generate_key();
+
+        assert_ne!(key, generate_key());
+        assert_eq!(decrypt(&encrypt(INPUT, &key), &key).as_deref(), Some(INPUT));
+    }
+
+    #[test]
+    #[should_panic]
+    fn passphrase() {
+        encrypt(INPUT, "correct horse battery staple");
+    }
+
+    #[test]
+    fn wrong_key() {
+        let encrypted = encrypt(INPUT, KEY);
+
+        assert_eq!(decrypt(&encrypted, OTHER), None);
+        assert_eq!(decrypt(INPUT, KEY), None);
+    }
+}
@@ -0,0 +1,338 @@
+use clap::{Args, ValueEnum};
+use serde::Deserialize;
+use std::{collections::HashMap, env, fmt, fs::read_to_string, path::PathBuf, time::Duration};
+
+const PROJECT_CONFIG: &str = "./aoc.toml";
+const USER_CONFIG: &str = "aoc/config.toml";
+
+const DEFAULT_YEAR: &str = "2024";
+const DEFAULT_CACHE_DIR: &str = "./input";
+const DEFAULT_CONTACT: &str = "https://github.com/Ben-Wormald/advent-of-code-2024";
+const DEFAULT_TIMEOUT: u64 = 30;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Plain,
+    Json,
+}
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputFormat::Plain => write!(f, "plain"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ConfigArgs {
+    /// Session cookie, overriding SESSION_COOKIE and config files
+    #[arg(long, global = true)]
+    session: Option<String>,
+    /// Named profile from the config files
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    #[arg(long, global = true)]
+    year: Option<String>,
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+    /// Contact details sent in the user agent
+    #[arg(long, global = true)]
+    contact: Option<String>,
+    #[arg(long, global = true)]
+    format: Option<OutputFormat>,
+    /// Request timeout in seconds
+    #[arg(long, global = true)]
+    timeout: Option<u64>,
+    /// Base64 key used to encrypt and decrypt cached inputs, see `config generate-key`
+    #[arg(long, global = true)]
+    input_key: Option<String>,
+    /// Pass inputs to solvers exactly as stored, without normalizing them
+    #[arg(long, global = true)]
+    raw: bool,
+    /// Also strip trailing whitespace from every input line
+    #[arg(long, global = true)]
+    trim_whitespace: bool,
+    /// Never touch the network, failing if an input is not cached
+    #[arg(long, global = true)]
+    offline: bool,
+    /// Report extra detail while solving, such as cache statistics
+    #[arg(short, long, global = true)]
+    verbose: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Layer {
+    session: Option<String>,
+    profile: Option<String>,
+    year: Option<String>,
+    cache_dir: Option<PathBuf>,
+    contact: Option<String>,
+    format: Option<OutputFormat>,
+    timeout: Option<u64>,
+    input_key: Option<String>,
+    normalize: Option<bool>,
+    trim_whitespace: Option<bool>,
+    offline: Option<bool>,
+    verbose: Option<bool>,
+    profiles: HashMap<String, Layer>,
+}
+impl Layer {
+    fn from_file(path: PathBuf) -> Layer {
+        match read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents)
+                .unwrap_or_else(|err| panic!("invalid config file {}: {err}", path.display())),
+            Err(_) => Layer::default(),
+        }
+    }
+
+    fn from_env() -> Layer {
+        Layer {
+            session: env::var("SESSION_COOKIE").ok(),
+            profile: env::var("AOC_PROFILE").ok(),
+            year: env::var("AOC_YEAR").ok(),
+            cache_dir: env::var("AOC_CACHE_DIR").ok().map(PathBuf::from),
+            contact: env::var("AOC_CONTACT").ok(),
+            format: env::var("AOC_FORMAT").ok().map(|format|
+                OutputFormat::from_str(&format, true)
+                    .unwrap_or_else(|_| panic!("invalid AOC_FORMAT {format}"))
+            ),
+            timeout: env::var("AOC_TIMEOUT").ok().map(|timeout| timeout
+                .parse()
+                .unwrap_or_else(|_| panic!("invalid AOC_TIMEOUT {timeout}"))
+            ),
+            input_key: env::var("AOC_INPUT_KEY").ok(),
+            normalize: get_env_flag("AOC_NORMALIZE"),
+            trim_whitespace: get_env_flag("AOC_TRIM_WHITESPACE"),
+            offline: get_env_flag("AOC_OFFLINE"),
+            verbose: get_env_flag("AOC_VERBOSE"),
+            profiles: HashMap::new(),
+        }
+    }
+
+    fn from_args(args: &ConfigArgs) -> Layer {
+        Layer {
+            session: args.session.clone(),
+            profile: args.profile.clone(),
+            year: args.year.clone(),
+            cache_dir: args.cache_dir.clone(),
+            contact: args.contact.clone(),
+            format: args.format,
+            timeout: args.timeout,
+            input_key: args.input_key.clone(),
+            normalize: args.raw.then_some(false),
+            trim_whitespace: args.trim_whitespace.then_some(true),
+            offline: args.offline.then_some(true),
+            verbose: args.verbose.then_some(true),
+            profiles: HashMap::new(),
+        }
+    }
+
+    /// Overlays `other` on top of `self`, so any value set in `other` wins.
+    fn merge(mut self, other: Layer) -> Layer {
+        for (name, profile) in other.profiles.into_iter() {
+            let merged = match self.profiles.remove(&name) {
+                Some(existing) => existing.merge(profile),
+                None => profile,
+            };
+            self.profiles.insert(name, merged);
+        }
+
+        Layer {
+            session: other.session.or(self.session),
+            profile: other.profile.or(self.profile),
+            year: other.year.or(self.year),
+            cache_dir: other.cache_dir.or(self.cache_dir),
+            contact: other.contact.or(self.contact),
+            format: other.format.or(self.format),
+            timeout: other.timeout.or(self.timeout),
+            input_key: other.input_key.or(self.input_key),
+            normalize: other.normalize.or(self.normalize),
+            trim_whitespace: other.trim_whitespace.or(self.trim_whitespace),
+            offline: other.offline.or(self.offline),
+            verbose: other.verbose.or(self.verbose),
+            profiles: self.profiles,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Config {
+    pub session: Option<String>,
+    pub profile: Option<String>,
+    pub year: String,
+    pub cache_dir: PathBuf,
+    pub contact: String,
+    pub format: OutputFormat,
+    pub timeout: Duration,
+    pub input_key: Option<String>,
+    pub normalize: bool,
+    pub trim_whitespace: bool,
+    pub offline: bool,
+    pub verbose: bool,
+}
+impl Config {
+    /// Resolves the effective config. Later sources override earlier ones: built-in defaults, the
+    /// user config, the project config, the selected profile, environment variables, CLI flags.
+    pub fn load(args: &ConfigArgs) -> Config {
+        let files = get_user_config_path()
+            .map(Layer::from_file)
+            .unwrap_or_default()
+            .merge(Layer::from_file(PathBuf::from(PROJECT_CONFIG)));
+
+        Config::resolve(files, Layer::from_env(), Layer::from_args(args))
+    }
+
+    fn resolve(mut files: Layer, env: Layer, args: Layer) -> Config {
+        let profile = args.profile.clone()
+            .or(env.profile.clone())
+            .or(files.profile.clone());
+
+        let profile_layer = match &profile {
+            Some(name) => files.profiles.remove(name)
+                .unwrap_or_else(|| panic!("no profile {name} found in config")),
+            None => Layer::default(),
+        };
+
+        let layer = files.merge(profile_layer).merge(env).merge(args);
+
+        Config {
+            session: layer.session,
+            profile,
+            year: layer.year.unwrap_or(DEFAULT_YEAR.to_string()),
+            cache_dir: layer.cache_dir.unwrap_or(PathBuf::from(DEFAULT_CACHE_DIR)),
+            contact: layer.contact.unwrap_or(DEFAULT_CONTACT.to_string()),
+            format: layer.format.unwrap_or_default(),
+            timeout: Duration::from_secs(layer.timeout.unwrap_or(DEFAULT_TIMEOUT)),
+            input_key: layer.input_key,
+            normalize: layer.normalize.unwrap_or(true),
+            trim_whitespace: layer.trim_whitespace.unwrap_or(false),
+            offline: layer.offline.unwrap_or(false),
+            verbose: layer.verbose.unwrap_or(false),
+        }
+    }
+
+    pub fn session(&self) -> &str {
+        self.session.as_deref().expect("no SESSION_COOKIE set!")
+    }
+
+    pub fn input_key(&self) -> &str {
+        self.input_key.as_deref().expect("no AOC_INPUT_KEY set!")
+    }
+}
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let session = self.session.as_deref().map(mask).unwrap_or(String::from("(not set)"));
+        let profile = self.profile.as_deref().unwrap_or("(none)");
+        let input_key = self.input_key.as_deref().map(mask).unwrap_or(String::from("(not set)"));
+
+        writeln!(f, "session         = {session}")?;
+        writeln!(f, "profile         = {profile}")?;
+        writeln!(f, "year            = {}", self.year)?;
+        writeln!(f, "cache_dir       = {}", self.cache_dir.display())?;
+        writeln!(f, "contact         = {}", self.contact)?;
+        writeln!(f, "format          = {}", self.format)?;
+        writeln!(f, "timeout         = {}", self.timeout.as_secs())?;
+        writeln!(f, "input_key       = {input_key}")?;
+        writeln!(f, "normalize       = {}", self.normalize)?;
+        writeln!(f, "trim_whitespace = {}", self.trim_whitespace)?;
+        writeln!(f, "offline         = {}", self.offline)?;
+        write!(f, "verbose         = {}", self.verbose)
+    }
+}
+
+fn get_user_config_path() -> Option<PathBuf> {
+    env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok()
+        .map(|dir| dir.join(USER_CONFIG))
+}
+
+fn get_env_flag(name: &str) -> Option<bool> {
+    env::var(name).ok().map(|flag| match flag.to_lowercase().as_str() {
+        "1" | "true" | "yes" => true,
+        "0" | "false" | "no" => false,
+        _ => panic!("invalid {name} {flag}"),
+    })
+}
+
+fn mask(token: &str) -> String {
+    match token.chars().count() {
+        len @ 0..=8 => "*".repeat(len),
+        len => {
+            let visible: String = token.chars().skip(len - 4).collect();
+            format!("{}{visible}", "*".repeat(len - 4))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: &str = "\
+        session = \"user-token\"\n\
+        year = \"2023\"\n\
+        timeout = 10\n\
+        \n\
+        [profiles.work]\n\
+        session = \"work-token\"\n\
+        format = \"json\"\n\
+    ";
+
+    const PROJECT: &str = "\
+        year = \"2024\"\n\
+        \n\
+        [profiles.work]\n\
+        cache_dir = \"./work\"\n\
+    ";
+
+    fn get_files() -> Layer {
+        let user: Layer = toml::from_str(USER).unwrap();
+        let project: Layer = toml::from_str(PROJECT).unwrap();
+        user.merge(project)
+    }
+
+    #[test]
+    fn layers() {
+        let config = Config::resolve(get_files(), Layer::default(), Layer::default());
+
+        assert_eq!(config.session.as_deref(), Some("user-token"));
+        assert_eq!(config.year, "2024");
+        assert_eq!(config.timeout, Duration::from_secs(10));
+        assert_eq!(config.format, OutputFormat::Plain);
+        assert_eq!(config.cache_dir, PathBuf::from(DEFAULT_CACHE_DIR));
+    }
+
+    #[test]
+    fn profiles() {
+        let args = Layer { profile: Some(String::from("work")), ..Layer::default() };
+        let config = Config::resolve(get_files(), Layer::default(), args);
+
+        assert_eq!(config.session.as_deref(), Some("work-token"));
+        assert_eq!(config.format, OutputFormat::Json);
+        assert_eq!(config.cache_dir, PathBuf::from("./work"));
+    }
+
+    #[test]
+    fn overrides() {
+        let env = Layer { session: Some(String::from("env-token")), year: Some(String::from("2022")), ..Layer::default() };
+        let args = Layer { year: Some(String::from("2021")), ..Layer::default() };
+        let config = Config::resolve(get_files(), env, args);
+
+        assert_eq!(config.session.as_deref(), Some("env-token"));
+        assert_eq!(config.year, "2021");
+    }
+
+    #[test]
+    fn masking() {
+        assert_eq!(mask("0123456789abcdef"), "************cdef");
+        assert_eq!(mask("abc"), "***");
+        assert_eq!(mask("abcdefgü123"), "*******ü123");
+        assert_eq!(mask("ééééé"), "*****");
+    }
+}
@@ -1,39 +1,252 @@
-use reqwest::{blocking::Client, header::{COOKIE, HeaderMap, HeaderValue, USER_AGENT}};
-use std::{env, fs::{read_to_string, File}, io::Write};
+use reqwest::{blocking::Client, header::{COOKIE, HeaderMap, HeaderValue, USER_AGENT}, StatusCode};
+use std::{
+    fs::{create_dir_all, read_dir, read_to_string, File},
+    io::Write,
+    path::PathBuf,
+    thread::sleep,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-const URL: &str = "https://adventofcode.com";
-const YEAR: &str = "2024";
+use crate::{cipher, config::Config, manifest};
 
-pub fn get_input(day: &str, test: bool) -> String {
-    match test {
-        true => read_file("test").expect("no test input found!"),
-        false => read_file(day).unwrap_or_else(|| fetch_and_save(day)),
+pub const URL: &str = "https://adventofcode.com";
+pub const DAYS: usize = 25;
+/// Puzzles unlock at midnight EST, which is 05:00 UTC.
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+pub fn get_input(day: &str, test: bool, config: &Config) -> String {
+    let input = match test {
+        true => read_file(&get_input_file("test"), config).expect("no test input found!"),
+        false => read_input(day, config),
+    };
+
+    match config.normalize {
+        true => normalize(&input, config.trim_whitespace),
+        false => input,
     }
 }
 
-fn read_file(file_name: &str) -> Option<String> {
-    let path = format!("./input/{file_name}.txt");
-    read_to_string(path).ok()
+fn read_input(day: &str, config: &Config) -> String {
+    let file_name = get_input_file(day);
+
+    match read_file(&file_name, config) {
+        Some(input) => {
+            manifest::check(&file_name, &input, config);
+            input
+        },
+        None if config.offline => {
+            let path = get_path(&file_name, config);
+            panic!("input not cached: {} (offline mode)", path.display());
+        },
+        None => fetch_and_save(day, config),
+    }
 }
 
-fn fetch_and_save(file_name: &str) -> String {
-    let client = get_client();
+/// Strips a leading BOM, converts CRLF line endings and ensures exactly one trailing newline,
+/// optionally trimming trailing whitespace from each line too.
+fn normalize(input: &str, trim_whitespace: bool) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
 
-    let day = file_name.replace("day_0", "").replace("day_", "");
-    let url = format!("{URL}/{YEAR}/day/{day}/input");
+    let mut lines: Vec<&str> = input
+        .lines()
+        .map(|line| match trim_whitespace {
+            true => line.trim_end(),
+            false => line,
+        })
+        .collect();
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    if lines.is_empty() {
+        String::new()
+    } else {
+        lines.join("\n") + "\n"
+    }
+}
+
+pub fn encrypt_inputs(config: &Config) {
+    for file_name in get_cached_files(config) {
+        let contents = read_to_string(get_path(&file_name, config)).unwrap();
+
+        if cipher::is_encrypted(&contents) {
+            println!("{file_name}: already encrypted");
+        } else {
+            save_file(&file_name, &cipher::encrypt(&contents, config.input_key()), config);
+            println!("{file_name}: encrypted");
+        }
+    }
+}
+
+pub fn decrypt_inputs(config: &Config) {
+    for file_name in get_cached_files(config) {
+        let contents = read_to_string(get_path(&file_name, config)).unwrap();
+
+        if cipher::is_encrypted(&contents) {
+            save_file(&file_name, &decrypt(&file_name, &contents, config), config);
+            println!("{file_name}: decrypted");
+        } else {
+            println!("{file_name}: already plain");
+        }
+    }
+}
+
+/// Reads a file from the cache directory, decrypting it if needed.
+pub fn read_file(file_name: &str, config: &Config) -> Option<String> {
+    let contents = read_to_string(get_path(file_name, config)).ok()?;
 
-    let input = client.get(url).send().unwrap().text().unwrap();
+    if cipher::is_encrypted(&contents) {
+        Some(decrypt(file_name, &contents, config))
+    } else {
+        Some(contents)
+    }
+}
+
+fn decrypt(file_name: &str, contents: &str, config: &Config) -> String {
+    cipher::decrypt(contents, config.input_key())
+        .unwrap_or_else(|| panic!("failed to decrypt {file_name}, is AOC_INPUT_KEY correct?"))
+}
+
+/// Writes a file to the cache directory, encrypting it if an input key is configured.
+pub fn save_cached(file_name: &str, contents: &str, config: &Config) {
+    match &config.input_key {
+        Some(key) => save_file(file_name, &cipher::encrypt(contents, key), config),
+        None => save_file(file_name, contents, config),
+    }
+}
 
-    let path = format!("./input/{file_name}.txt");
-    let mut file = File::create(path).unwrap();
-    file.write_all(input.as_bytes()).ok();
+fn save_file(file_name: &str, contents: &str, config: &Config) {
+    create_dir_all(&config.cache_dir).ok();
+    let mut file = File::create(get_path(file_name, config)).unwrap();
+    file.write_all(contents.as_bytes()).ok();
+}
+
+pub fn get_path(file_name: &str, config: &Config) -> PathBuf {
+    config.cache_dir.join(file_name)
+}
 
-    input
+fn get_input_file(day: &str) -> String {
+    format!("{day}.txt")
 }
 
-fn get_client() -> Client {
-    let session_cookie = env::var("SESSION_COOKIE").expect("no SESSION_COOKIE set!");
-    let session_cookie = format!("session={session_cookie}");
+/// Every cached input and puzzle description, excluding the public test input.
+pub fn get_cached_files(config: &Config) -> Vec<String> {
+    let mut files: Vec<String> = read_dir(&config.cache_dir)
+        .map(|entries| entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.starts_with("day_") && (name.ends_with(".txt") || name.ends_with(".html")))
+            .collect()
+        )
+        .unwrap_or_default();
+
+    files.sort();
+    files
+}
+
+/// Fetches the given day if it is not already cached.
+pub fn fetch(day: &str, config: &Config) {
+    if get_path(&get_input_file(day), config).exists() {
+        println!("{day}: cached");
+    } else {
+        fetch_and_save(day, config);
+        println!("{day}: downloaded");
+    }
+}
+
+/// Fetches every unlocked day that is not already cached, waiting `delay` between requests.
+pub fn fetch_all(config: &Config, delay: Duration) {
+    let year = config.year.parse().expect("invalid year");
+    let mut client = None;
+
+    let (mut downloaded, mut cached, mut locked, mut failed) = (0, 0, 0, 0);
+
+    for day in 1..=DAYS {
+        let file_name = format!("day_{day:02}");
+
+        if get_path(&get_input_file(&file_name), config).exists() {
+            println!("{file_name}: cached");
+            cached += 1;
+            continue;
+        }
+
+        if !is_unlocked(year, day) {
+            println!("{file_name}: locked");
+            locked += 1;
+            continue;
+        }
+
+        if config.offline {
+            println!("{file_name}: not cached (offline mode)");
+            failed += 1;
+            continue;
+        }
+
+        if client.is_some() {
+            sleep(delay);
+        }
+        let client = client.get_or_insert_with(|| get_client(config));
+
+        match try_fetch_and_save(client, &file_name, config) {
+            Ok(_) => {
+                println!("{file_name}: downloaded");
+                downloaded += 1;
+            },
+            Err(err) if err.status() == Some(StatusCode::NOT_FOUND) => {
+                println!("{file_name}: locked");
+                locked += 1;
+            },
+            Err(err) => {
+                println!("{file_name}: failed ({err})");
+                failed += 1;
+            },
+        }
+    }
+
+    println!("downloaded {downloaded}, cached {cached}, locked {locked}, failed {failed}");
+}
+
+fn is_unlocked(year: i64, day: usize) -> bool {
+    let unlock_secs = get_days_since_epoch(year, 12, day as i64) * 86_400 + UNLOCK_HOUR_UTC as i64 * 3_600;
+    let now_secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+
+    now_secs >= unlock_secs
+}
+
+/// Days from 1970-01-01 to the given date in the proleptic Gregorian calendar.
+fn get_days_since_epoch(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn fetch_and_save(file_name: &str, config: &Config) -> String {
+    try_fetch_and_save(&get_client(config), file_name, config)
+        .unwrap_or_else(|err| panic!("failed to fetch {file_name}: {err}"))
+}
+
+fn try_fetch_and_save(client: &Client, file_name: &str, config: &Config) -> reqwest::Result<String> {
+    let day = file_name.replace("day_0", "").replace("day_", "");
+    let url = format!("{URL}/{}/day/{day}/input", config.year);
+
+    let input = client.get(url).send()?.error_for_status()?.text()?;
+    save_cached(&get_input_file(file_name), &input, config);
+    manifest::record(&get_input_file(file_name), &input, config);
+
+    Ok(input)
+}
+
+pub fn get_client(config: &Config) -> Client {
+    if config.offline {
+        panic!("network access is disabled in offline mode");
+    }
+
+    let session_cookie = format!("session={}", config.session());
 
     let mut headers = HeaderMap::new();
     headers.insert(
@@ -42,10 +255,50 @@ fn get_client() -> Client {
     );
     headers.insert(
         USER_AGENT,
-        HeaderValue::from_static("https://github.com/Ben-Wormald/advent-of-code-2024"),
+        HeaderValue::from_str(&config.contact).expect("invalid contact for user agent"),
     );
 
     Client::builder()
         .default_headers(headers)
+        .timeout(config.timeout)
         .build().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_since_epoch() {
+        assert_eq!(get_days_since_epoch(1970, 1, 1), 0);
+        assert_eq!(get_days_since_epoch(2000, 3, 1), 11_017);
+        assert_eq!(get_days_since_epoch(2024, 12, 1), 20_058);
+    }
+
+    #[test]
+    fn unlocked() {
+        assert!(is_unlocked(2015, 25));
+        assert!(!is_unlocked(9999, 1));
+    }
+
+    #[test]
+    fn normalize_line_endings() {
+        let input = "\u{feff}1,2\r\n\r\n3,4\r\n\r\n\r\n";
+
+        assert_eq!(normalize(input, false), "1,2\n\n3,4\n");
+    }
+
+    #[test]
+    fn normalize_missing_newline() {
+        assert_eq!(normalize("125 17", false), "125 17\n");
+        assert_eq!(normalize("\n\n", false), "");
+    }
+
+    #[test]
+    fn normalize_trailing_whitespace() {
+        let input = "#..# \n.#.\t\n";
+
+        assert_eq!(normalize(input, false), input);
+        assert_eq!(normalize(input, true), "#..#\n.#.\n");
+    }
+}
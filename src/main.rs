@@ -1,7 +1,25 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::{panic, time::Duration};
+
+mod account;
+
+mod cipher;
+
+mod config;
+use config::{Config, ConfigArgs, OutputFormat};
 
 mod input;
-use input::get_input;
+use input::{decrypt_inputs, encrypt_inputs, fetch, fetch_all, get_input};
+
+mod manifest;
+
+mod puzzle;
+use puzzle::Style;
+
+// Only one day is compiled at a time, so most shared helpers are unused in any given build.
+#[allow(dead_code)]
+mod utils;
+use utils::{memo, parse::ParseError};
 
 macro_rules! day {
     ($day:ident) => {
@@ -13,11 +31,75 @@ macro_rules! day {
 }
 
 #[derive(Parser)]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
-    #[arg(short, long)]
-    part: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(short, long, required = true)]
+    part: Option<String>,
     #[arg(short, long)]
     test: bool,
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect the resolved configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Download the current day's input, or every unlocked input, if not already cached
+    Fetch {
+        #[arg(long)]
+        all: bool,
+        /// Seconds to wait between requests
+        #[arg(long, default_value_t = 1)]
+        delay: u64,
+    },
+    /// Print the current day's puzzle description
+    Read {
+        /// Render as Markdown instead of terminal text
+        #[arg(long)]
+        markdown: bool,
+        /// Fetch the description again even if it is cached, such as once part two unlocks
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Check the session cookie and show who it belongs to
+    Whoami,
+    /// Compare stars earned upstream with local solutions
+    Progress,
+    /// Check cached inputs against the checksums recorded when they were fetched
+    Manifest {
+        #[command(subcommand)]
+        command: ManifestCommand,
+    },
+    /// Encrypt every cached input in place using the configured input key
+    EncryptInputs,
+    /// Decrypt every cached input in place using the configured input key
+    DecryptInputs,
+}
+
+#[derive(Subcommand)]
+enum ManifestCommand {
+    /// Record checksums for cached inputs not yet in the manifest
+    Update {
+        /// Re-record inputs that are already in the manifest
+        #[arg(long)]
+        force: bool,
+    },
+    /// Report any cached input that no longer matches the manifest
+    Verify,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the effective configuration with the session token masked
+    Show,
+    /// Print a new random key to use as AOC_INPUT_KEY
+    GenerateKey,
 }
 
 day!(day_18);
@@ -25,13 +107,56 @@ day!(day_18);
 fn main() {
     dotenv::dotenv().ok();
     let args = Args::parse();
+    let config = Config::load(&args.config);
+
+    match args.command {
+        Some(Command::Config { command: ConfigCommand::Show }) => println!("{config}"),
+        Some(Command::Config { command: ConfigCommand::GenerateKey }) => println!("{}", cipher::generate_key()),
+        Some(Command::Fetch { all: true, delay }) => fetch_all(&config, Duration::from_secs(delay)),
+        Some(Command::Fetch { all: false, .. }) => fetch(INPUT, &config),
+        Some(Command::Read { markdown, refresh }) => {
+            let style = if markdown { Style::Markdown } else { Style::Terminal };
+            puzzle::read(INPUT, style, refresh, &config);
+        },
+        Some(Command::Whoami) => account::whoami(&config),
+        Some(Command::Progress) => account::progress(&config),
+        Some(Command::Manifest { command: ManifestCommand::Update { force } }) => manifest::update(force, &config),
+        Some(Command::Manifest { command: ManifestCommand::Verify }) => manifest::verify_all(&config),
+        Some(Command::EncryptInputs) => encrypt_inputs(&config),
+        Some(Command::DecryptInputs) => decrypt_inputs(&config),
+        None => solve(&args.part.unwrap(), args.test, &config),
+    }
+}
 
-    let input = get_input(INPUT, args.test);
+fn solve(part: &str, test: bool, config: &Config) {
+    let input = get_input(INPUT, test, config);
+    report_parse_errors(&input);
+    memo::set_verbose(config.verbose);
 
-    let solution = match args.part.as_str() {
+    let solution = match part {
         "1" | "one" => solve_part_one(&input),
         "2" | "two" => solve_part_two(&input),
         _ => panic!(),
     };
-    println!("{solution}");
+
+    match config.format {
+        OutputFormat::Plain => println!("{solution}"),
+        OutputFormat::Json => println!("{}", serde_json::json!({
+            "day": INPUT,
+            "part": part,
+            "answer": solution.to_string(),
+        })),
+    }
+}
+
+/// Shows a parse error raised by a solver against the line of input it came from, rather than as
+/// a bare panic.
+fn report_parse_errors(input: &str) {
+    let input = input.to_string();
+    let default_hook = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| match info.payload().downcast_ref::<ParseError>() {
+        Some(err) => eprintln!("{}", err.render(&input)),
+        None => default_hook(info),
+    }));
 }
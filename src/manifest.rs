@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, fs::{metadata, read_to_string, write}, io, time::{SystemTime, UNIX_EPOCH}};
+
+use crate::{config::Config, input::{get_cached_files, get_path, read_file}};
+
+const MANIFEST_FILE: &str = "manifest.toml";
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Entry {
+    sha256: String,
+    size: usize,
+    /// Seconds since the Unix epoch.
+    fetched_at: u64,
+}
+impl Entry {
+    fn new(contents: &str, fetched_at: SystemTime) -> Entry {
+        Entry {
+            sha256: get_hash(contents),
+            size: contents.len(),
+            fetched_at: fetched_at.duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Unrecorded,
+    Valid,
+    Truncated { expected: usize, found: usize },
+    Modified,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Manifest {
+    #[serde(flatten)]
+    entries: BTreeMap<String, Entry>,
+}
+impl Manifest {
+    /// Reads the manifest, which is empty if there isn't one yet.
+    pub fn read(config: &Config) -> Result<Manifest, String> {
+        let path = config.cache_dir.join(MANIFEST_FILE);
+
+        match read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|err| format!("invalid manifest {}: {err}", path.display())),
+            Err(_) => Ok(Manifest::default()),
+        }
+    }
+
+    /// Like `read`, but panics if the manifest is malformed, for the commands that manage it.
+    pub fn load(config: &Config) -> Manifest {
+        Manifest::read(config).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn save(&self, config: &Config) -> io::Result<()> {
+        let contents = toml::to_string(self).unwrap();
+        write(config.cache_dir.join(MANIFEST_FILE), contents)
+    }
+
+    pub fn contains(&self, file_name: &str) -> bool {
+        self.entries.contains_key(file_name)
+    }
+
+    pub fn record(&mut self, file_name: &str, contents: &str, fetched_at: SystemTime) {
+        self.entries.insert(file_name.to_string(), Entry::new(contents, fetched_at));
+    }
+
+    pub fn verify(&self, file_name: &str, contents: &str) -> Status {
+        match self.entries.get(file_name) {
+            None => Status::Unrecorded,
+            Some(entry) if entry.sha256 == get_hash(contents) => Status::Valid,
+            Some(entry) if contents.len() < entry.size => Status::Truncated {
+                expected: entry.size,
+                found: contents.len(),
+            },
+            Some(_) => Status::Modified,
+        }
+    }
+}
+
+/// Records a freshly fetched file in the manifest. The file is already saved by now, so failing to
+/// record it is only a warning.
+pub fn record(file_name: &str, contents: &str, config: &Config) {
+    let mut manifest = match Manifest::read(config) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            eprintln!("warning: not recording {file_name}, {err}");
+            return;
+        }
+    };
+    manifest.record(file_name, contents, SystemTime::now());
+
+    if let Err(err) = manifest.save(config) {
+        eprintln!("warning: failed to record {file_name} in the manifest: {err}");
+    }
+}
+
+/// Warns if a cached file no longer matches what was fetched, or if the manifest can't be read
+/// to tell.
+pub fn check(file_name: &str, contents: &str, config: &Config) {
+    let manifest = match Manifest::read(config) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            eprintln!("warning: skipping the check of {file_name}, {err}");
+            return;
+        }
+    };
+
+    if let Some(warning) = get_warning(file_name, &manifest.verify(file_name, contents)) {
+        eprintln!("warning: {warning}");
+    }
+}
+
+/// Records every cached input missing from the manifest, or every input if `force` is set. Files
+/// already on disk are dated by when they were last modified, the nearest record of their fetch.
+pub fn update(force: bool, config: &Config) {
+    let mut manifest = Manifest::load(config);
+
+    for file_name in get_cached_inputs(config) {
+        if manifest.contains(&file_name) && !force {
+            println!("{file_name}: already recorded");
+        } else {
+            let modified = metadata(get_path(&file_name, config))
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or_else(|_| SystemTime::now());
+
+            manifest.record(&file_name, &read_file(&file_name, config).unwrap(), modified);
+            println!("{file_name}: recorded");
+        }
+    }
+
+    manifest
+        .save(config)
+        .unwrap_or_else(|err| panic!("failed to save manifest in {}: {err}", config.cache_dir.display()));
+}
+
+/// Verifies every cached input against the manifest.
+pub fn verify_all(config: &Config) {
+    let manifest = Manifest::load(config);
+
+    for file_name in get_cached_inputs(config) {
+        let status = manifest.verify(&file_name, &read_file(&file_name, config).unwrap());
+
+        match get_warning(&file_name, &status) {
+            Some(warning) => println!("{warning}"),
+            None if status == Status::Unrecorded => println!("{file_name}: not recorded"),
+            None => println!("{file_name}: ok"),
+        }
+    }
+}
+
+fn get_cached_inputs(config: &Config) -> Vec<String> {
+    get_cached_files(config)
+        .into_iter()
+        .filter(|file_name| file_name.ends_with(".txt"))
+        .collect()
+}
+
+fn get_warning(file_name: &str, status: &Status) -> Option<String> {
+    match status {
+        Status::Unrecorded | Status::Valid => None,
+        Status::Truncated { expected, found } => Some(format!(
+            "{file_name} looks truncated, it was {expected} bytes when fetched but is now {found}"
+        )),
+        Status::Modified => Some(format!(
+            "{file_name} has been modified since it was fetched, its checksum no longer matches"
+        )),
+    }
+}
+
+fn get_hash(contents: &str) -> String {
+    format!("{:x}", Sha256::digest(contents.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const INPUT: &str = "125 17\n";
+
+    #[test]
+    fn verify() {
+        let mut manifest = Manifest::default();
+
+        assert_eq!(manifest.verify("day_11.txt", INPUT), Status::Unrecorded);
+
+        manifest.record("day_11.txt", INPUT, SystemTime::now());
+
+        assert_eq!(manifest.verify("day_11.txt", INPUT), Status::Valid);
+        assert_eq!(manifest.verify("day_11.txt", "125 18\n"), Status::Modified);
+        assert_eq!(manifest.verify("day_11.txt", "125"), Status::Truncated { expected: 7, found: 3 });
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut manifest = Manifest::default();
+        manifest.record("day_11.txt", INPUT, UNIX_EPOCH + Duration::from_secs(1_733_029_200));
+
+        let serialized = toml::to_string(&manifest).unwrap();
+        let deserialized: Manifest = toml::from_str(&serialized).unwrap();
+
+        assert!(serialized.starts_with("[\"day_11.txt\"]\n"));
+        assert_eq!(deserialized.entries, manifest.entries);
+        assert_eq!(manifest.entries["day_11.txt"].fetched_at, 1_733_029_200);
+    }
+}
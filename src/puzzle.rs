@@ -0,0 +1,428 @@
+use crate::{config::Config, input::{get_client, read_file, save_cached, URL}};
+
+const ARTICLE_START: &str = "<article class=\"day-desc\">";
+const ARTICLE_END: &str = "</article>";
+const ANSWER_START: &str = "<p>Your puzzle answer was <code>";
+const ANSWER_END: &str = "</code>";
+
+const BOLD: &str = "\x1b[1m";
+const BRIGHT: &str = "\x1b[1;97m";
+const UNDERLINE: &str = "\x1b[4m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Style {
+    Terminal,
+    Markdown,
+}
+
+/// Prints the puzzle description for a day, using the cached copy unless it is missing or
+/// `refresh` is set, such as once part two unlocks.
+pub fn read(day: &str, style: Style, refresh: bool, config: &Config) {
+    let file_name = format!("{day}.html");
+
+    let html = match read_file(&file_name, config) {
+        Some(cached) if !refresh || config.offline => cached,
+        None if config.offline => panic!("puzzle not cached: {file_name} (offline mode)"),
+        cached => match fetch_puzzle(day, config) {
+            Ok(html) => {
+                let puzzle = extract(&html);
+
+                match cached {
+                    _ if !get_articles(&puzzle).is_empty() => {
+                        save_cached(&file_name, &puzzle, config);
+                        puzzle
+                    },
+                    // Such as when the session has expired and the page only shows a login prompt.
+                    Some(cached) => {
+                        eprintln!("warning: fetched page for {day} has no puzzle description, showing the cached copy");
+                        cached
+                    },
+                    None => puzzle,
+                }
+            },
+            Err(err) => cached.unwrap_or_else(|| panic!("failed to fetch puzzle for {day}: {err}")),
+        },
+    };
+
+    println!("{}", render(&html, style));
+}
+
+fn fetch_puzzle(day: &str, config: &Config) -> reqwest::Result<String> {
+    let day = day.replace("day_0", "").replace("day_", "");
+    let url = format!("{URL}/{}/day/{day}", config.year);
+
+    get_client(config).get(url).send()?.error_for_status()?.text()
+}
+
+/// The parts of a puzzle page worth caching: the descriptions and any accepted answers, but not the
+/// header naming the logged-in user.
+fn extract(html: &str) -> String {
+    let articles = get_articles(html)
+        .into_iter()
+        .map(|article| format!("{ARTICLE_START}{article}{ARTICLE_END}"));
+    let answers = get_raw_answers(html)
+        .into_iter()
+        .map(|answer| format!("{ANSWER_START}{answer}{ANSWER_END}.</p>"));
+
+    articles.chain(answers).map(|block| block + "\n").collect()
+}
+
+/// The answers upstream has accepted, in part order.
+pub fn get_answers(html: &str) -> Vec<String> {
+    get_raw_answers(html).into_iter().map(decode_entities).collect()
+}
+
+fn get_raw_answers(html: &str) -> Vec<&str> {
+    html
+        .split(ANSWER_START)
+        .skip(1)
+        .filter_map(|answer| answer.split_once(ANSWER_END).map(|(answer, _rest)| answer))
+        .collect()
+}
+
+fn get_articles(html: &str) -> Vec<&str> {
+    html
+        .split(ARTICLE_START)
+        .skip(1)
+        .filter_map(|article| article.split_once(ARTICLE_END).map(|(article, _rest)| article))
+        .collect()
+}
+
+fn render(html: &str, style: Style) -> String {
+    let articles = get_articles(html);
+
+    if articles.is_empty() {
+        panic!("no puzzle description found, is SESSION_COOKIE valid?");
+    }
+
+    articles
+        .into_iter()
+        .map(|article| Renderer::new(style).render(article))
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(tag) = rest.strip_prefix('<') {
+            let (tag, after) = tag.split_once('>').unwrap_or((tag, ""));
+            let tag = tag.trim_end_matches('/');
+
+            if let Some(name) = tag.strip_prefix('/') {
+                tokens.push(Token::Close(name.trim()));
+            } else {
+                let (name, attributes) = tag.split_once(' ').unwrap_or((tag, ""));
+                tokens.push(Token::Open(name, attributes));
+            }
+
+            rest = after;
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    tokens
+}
+
+fn get_attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{name}=\""))? + name.len() + 2;
+    let length = attributes[start..].find('"')?;
+    Some(&attributes[start..start + length])
+}
+
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+
+        let character = entity.and_then(|(entity, _end)| match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        });
+
+        match (character, entity) {
+            (Some(character), Some((_entity, end))) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            },
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+struct Renderer {
+    style: Style,
+    output: String,
+    in_pre: bool,
+    lists: usize,
+    links: Vec<String>,
+}
+impl Renderer {
+    fn new(style: Style) -> Renderer {
+        Renderer {
+            style,
+            output: String::new(),
+            in_pre: false,
+            lists: 0,
+            links: Vec::new(),
+        }
+    }
+
+    fn render(mut self, article: &str) -> String {
+        for token in tokenize(article) {
+            match token {
+                Token::Open(name, attributes) => self.open(name, attributes),
+                Token::Close(name) => self.close(name),
+                Token::Text(text) => self.text(text),
+            }
+        }
+
+        self.output.trim().to_string()
+    }
+
+    fn open(&mut self, name: &str, attributes: &str) {
+        let markdown = self.style == Style::Markdown;
+
+        match name {
+            "h2" => {
+                self.block();
+                self.push(if markdown { "## " } else { BOLD });
+            },
+            "p" => self.block(),
+            "pre" => {
+                self.block();
+                self.in_pre = true;
+                if markdown {
+                    self.push("```\n");
+                }
+            },
+            "ul" | "ol" => {
+                if self.lists == 0 {
+                    self.block();
+                }
+                self.lists += 1;
+            },
+            "li" => {
+                self.line();
+                let indent = "  ".repeat(self.lists.saturating_sub(1));
+                self.push(&format!("{indent}- "));
+            },
+            "em" if !markdown => self.push(BRIGHT),
+            "em" if !self.in_pre => self.push("*"),
+            "code" if !self.in_pre => self.push(if markdown { "`" } else { BOLD }),
+            "a" => {
+                let href = get_attribute(attributes, "href").unwrap_or("");
+                let href = match href.starts_with('/') {
+                    true => format!("{URL}{href}"),
+                    false => href.to_string(),
+                };
+                self.links.push(href);
+                self.push(if markdown { "[" } else { UNDERLINE });
+            },
+            _ => (),
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        let markdown = self.style == Style::Markdown;
+
+        match name {
+            "h2" => {
+                if !markdown {
+                    self.push(RESET);
+                }
+                self.block();
+            },
+            "p" => self.block(),
+            "pre" => {
+                self.in_pre = false;
+                self.line();
+                if markdown {
+                    self.push("```\n");
+                }
+                self.block();
+            },
+            "ul" | "ol" => {
+                self.lists = self.lists.saturating_sub(1);
+                if self.lists == 0 {
+                    self.block();
+                }
+            },
+            "em" if !markdown => self.push(RESET),
+            "em" if !self.in_pre => self.push("*"),
+            "code" if !self.in_pre => self.push(if markdown { "`" } else { RESET }),
+            "a" => {
+                let href = self.links.pop().unwrap_or_default();
+                match markdown {
+                    true => self.push(&format!("]({href})")),
+                    false => self.push(&format!("{RESET} ({href})")),
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+
+        if self.in_pre {
+            let indented = match self.style {
+                Style::Markdown => text,
+                Style::Terminal => text.replace('\n', "\n    "),
+            };
+
+            if self.style == Style::Terminal && self.output.ends_with('\n') {
+                self.push("    ");
+            }
+            self.push(&indented);
+        } else {
+            let collapsed = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+            if text.starts_with(char::is_whitespace) && !self.at_line_start() {
+                self.push(" ");
+            }
+            self.push(&collapsed);
+            if text.ends_with(char::is_whitespace) && !collapsed.is_empty() {
+                self.push(" ");
+            }
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        self.output.push_str(text);
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n') || self.output.ends_with(' ')
+    }
+
+    /// Ends the current line, if it has any content.
+    fn line(&mut self) {
+        let trimmed = self.output.trim_end_matches([' ', '\t']).len();
+        self.output.truncate(trimmed);
+
+        if !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.push("\n");
+        }
+    }
+
+    /// Separates blocks with a single blank line.
+    fn block(&mut self) {
+        self.line();
+
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+            self.push("\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = "\
+        <html><body><header><div class=\"user\">Ben Wormald <span class=\"star-count\">34*</span></div></header><main>\n\
+        <article class=\"day-desc\"><h2>--- Day 1: Historian Hysteria ---</h2>\
+        <p>The <em>Chief Historian</em> is always present &amp; <a href=\"/2024/events\">accounted</a> for.</p>\n\
+        <pre><code>3   4\n\
+        4   3\n\
+        </code></pre>\n\
+        <ul>\n\
+        <li>The <code>left</code> list.</li>\n\
+        <li>The right list.</li>\n\
+        </ul>\n\
+        <p>What is the <em>total distance</em>?</p>\n\
+        </article>\n\
+        <p>Your puzzle answer was <code>11</code>.</p>\
+        <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>Figure out &lt;exactly&gt; how often.</p></article>\n\
+        </main></body></html>\n\
+    ";
+
+    #[test]
+    fn articles() {
+        assert_eq!(get_articles(HTML).len(), 2);
+        assert_eq!(get_articles("<html></html>").len(), 0);
+    }
+
+    #[test]
+    fn extracted() {
+        let puzzle = extract(HTML);
+
+        assert!(!puzzle.contains("Ben Wormald"));
+        assert_eq!(get_articles(&puzzle), get_articles(HTML));
+        assert_eq!(get_answers(&puzzle), vec!["11"]);
+        assert_eq!(render(&puzzle, Style::Markdown), render(HTML, Style::Markdown));
+    }
+
+    #[test]
+    fn markdown() {
+        let expected = "\
+            ## --- Day 1: Historian Hysteria ---\n\
+            \n\
+            The *Chief Historian* is always present & [accounted](https://adventofcode.com/2024/events) for.\n\
+            \n\
+            ```\n\
+            3   4\n\
+            4   3\n\
+            ```\n\
+            \n\
+            - The `left` list.\n\
+            - The right list.\n\
+            \n\
+            What is the *total distance*?\n\
+            \n\
+            ## --- Part Two ---\n\
+            \n\
+            Figure out <exactly> how often.\
+        ";
+
+        assert_eq!(render(HTML, Style::Markdown), expected);
+    }
+
+    #[test]
+    fn terminal() {
+        let rendered = render(HTML, Style::Terminal);
+
+        assert!(rendered.starts_with(&format!("{BOLD}--- Day 1: Historian Hysteria ---{RESET}\n\n")));
+        assert!(rendered.contains(&format!("The {BRIGHT}Chief Historian{RESET} is always present")));
+        assert!(rendered.contains("\n    3   4\n    4   3\n"));
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(decode_entities("a &lt;b&gt; &#39;c&#x27; &unknown; & d"), "a <b> 'c' &unknown; & d");
+    }
+}
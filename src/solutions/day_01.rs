@@ -1,7 +1,7 @@
-use itertools::Itertools;
+use crate::utils::parse::{each_line, numbers_n, raise, ParseError, WHITESPACE};
 
 pub fn solve_part_one(input: &str) -> usize {
-    let mut pairs = get_pairs(input);
+    let mut pairs = get_pairs(input).unwrap_or_else(raise!());
     
     pairs.0.sort();
     pairs.1.sort();
@@ -15,7 +15,7 @@ pub fn solve_part_one(input: &str) -> usize {
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let pairs = get_pairs(input);
+    let pairs = get_pairs(input).unwrap_or_else(raise!());
 
     pairs.0.iter()
         .fold(0, |sum, id_a| {
@@ -24,17 +24,13 @@ pub fn solve_part_two(input: &str) -> usize {
         })
 }
 
-fn get_pairs(input: &str) -> (Vec<usize>, Vec<usize>) {
-    let pairs: Vec<(usize, usize)> = input
-        .lines()
-        .map(|pair| pair
-            .split_whitespace()
-            .map(|id| id.parse::<usize>().unwrap())
-            .collect_tuple::<(usize, usize)>()
-            .unwrap()
-        ).collect();
+fn get_pairs(input: &str) -> Result<(Vec<usize>, Vec<usize>), ParseError> {
+    let pairs = each_line(input, |pair| {
+        let [id_a, id_b] = numbers_n::<usize, 2>(pair, WHITESPACE)?;
+        Ok((id_a, id_b))
+    })?;
 
-    pairs.into_iter().unzip()
+    Ok(pairs.into_iter().unzip())
 }
 
 #[cfg(test)]
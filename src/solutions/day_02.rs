@@ -1,3 +1,5 @@
+use crate::utils::parse::{each_line, numbers, raise, ParseError, WHITESPACE};
+
 trait Report {
     fn is_safe(&self) -> bool;
     fn without(&self, index: usize) -> Self;
@@ -27,12 +29,16 @@ impl Report for Vec<usize> {
 
 pub fn solve_part_one(input: &str) -> usize {
     get_reports(input)
+        .unwrap_or_else(raise!())
+        .into_iter()
         .filter(|report| report.is_safe())
         .count()
 }
 
 pub fn solve_part_two(input: &str) -> usize {
     get_reports(input)
+        .unwrap_or_else(raise!())
+        .into_iter()
         .filter(|report| {
             let mut is_safe = report.is_safe();
 
@@ -48,13 +54,8 @@ pub fn solve_part_two(input: &str) -> usize {
         .count()
 }
 
-fn get_reports(input: &str) -> impl Iterator<Item = Vec<usize>> + use<'_> {
-    input.lines()
-        .map(|report| report
-            .split_whitespace()
-            .map(|level| level.parse().unwrap())
-            .collect::<Vec<usize>>()
-    )
+fn get_reports(input: &str) -> Result<Vec<Vec<usize>>, ParseError> {
+    each_line(input, |report| numbers(report, WHITESPACE))
 }
 
 #[cfg(test)]
@@ -83,4 +84,15 @@ mod tests {
 
         assert_eq!(solve_part_two(INPUT), expected);
     }
+
+    #[test]
+    fn whitespace() {
+        let expected = vec![vec![7, 6, 4], vec![1, 3]];
+
+        assert_eq!(get_reports("7\t6  4\n 1 3 \n"), Ok(expected));
+
+        let err = get_reports("1 2\n7 6 x4\n").unwrap_err();
+
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "x4"));
+    }
 }
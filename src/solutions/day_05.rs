@@ -1,101 +1,48 @@
-use itertools::Itertools;
-use std::cmp::Ordering;
-
-struct Page {
-    number: usize,
-    rules: Vec<(usize, usize)>,
-}
-impl Ord for Page {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.rules.contains(&(self.number, other.number)) {
-            Ordering::Less
-        } else if self.rules.contains(&(other.number, self.number)) {
-            Ordering::Greater
-        } else {
-            Ordering::Equal
-        }
-    }
-}
-impl PartialOrd for Page {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-impl PartialEq for Page {
-    fn eq(&self, other: &Self) -> bool {
-        self.number == other.number
-    }
-}
-impl Eq for Page {}
+use crate::utils::{
+    dag::Dag,
+    parse::{each_line, numbers, numbers_n, raise, sections_n, ParseError},
+};
 
 pub fn solve_part_one(input: &str) -> usize {
-    let updates = get_updates(input);
+    let (rules, updates) = get_manual(input).unwrap_or_else(raise!());
 
-    updates.into_iter().fold(0, |sum, update| {
-        if update.is_sorted() {
-            sum + get_middle_page(&update)
-        } else {
-            sum
-        }
-    })
+    updates
+        .iter()
+        .filter(|update| rules.respects(update))
+        .map(|update| get_middle_page(update))
+        .sum()
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let updates = get_updates(input);
+    let (rules, updates) = get_manual(input).unwrap_or_else(raise!());
 
-    updates.into_iter().fold(0, |sum, update| {
-        if !update.is_sorted() {
-            let mut update = update;
-            update.sort();
-            sum + get_middle_page(&update)
-        } else {
-            sum
-        }
-    })
+    updates
+        .iter()
+        .filter(|update| !rules.respects(update))
+        .map(|update| {
+            // The rules as a whole contain cycles, but those between the pages of one update don't.
+            let sorted = rules.subgraph(update).topological_sort().unwrap_or_else(|err| panic!("{err}"));
+            get_middle_page(&sorted)
+        })
+        .sum()
 }
 
-fn get_updates(input: &str) -> Vec<Vec<Page>> {
-    let (rules, updates) = input.split("\n\n").collect_tuple().unwrap();
-
-    let rules: Vec<(usize, usize)> = rules
-        .lines()
-        .map(|rule| rule
-            .split("|")
-            .map(|page| page.parse().unwrap())
-            .collect_tuple().unwrap()
-        )
-        .collect();
-
-    let updates = updates
-        .lines()
-        .map(|update| {
-            let pages = update
-                .split(",")
-                .map(|page| page.parse().unwrap())
-                .collect::<Vec<usize>>();
+fn get_manual(input: &str) -> Result<(Dag<usize>, Vec<Vec<usize>>), ParseError> {
+    let [rules, updates] = sections_n(input)?;
 
-            pages.iter()
-                .map(|page| Page {
-                    number: *page,
-                    rules: get_matching_rules(&pages, &rules),
-                })
-                .collect()
-        })
-        .collect();
+    let rules = each_line(rules, |rule| {
+        let [before, after] = numbers_n(rule, &['|'])?;
+        Ok((before, after))
+    }).map_err(|err| err.within(input, rules))?;
 
-    updates
-}
+    let updates = each_line(updates, |update| numbers(update, &[',']))
+        .map_err(|err| err.within(input, updates))?;
 
-fn get_matching_rules(update: &[usize], rules: &[(usize, usize)]) -> Vec<(usize, usize)> {
-    rules
-        .iter()
-        .copied()
-        .filter(|rule| update.contains(&rule.0) && update.contains(&rule.1))
-        .collect()
+    Ok((Dag::from_edges(rules), updates))
 }
 
-fn get_middle_page(update: &[Page]) -> usize {
-    update.get(update.len() / 2).unwrap().number
+fn get_middle_page(update: &[usize]) -> usize {
+    update[update.len() / 2]
 }
 
 #[cfg(test)]
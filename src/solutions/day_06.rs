@@ -1,193 +1,95 @@
-use std::{collections::HashSet, ops::Add};
-
-#[derive(Clone)]
-struct Grid {
-    cells: Vec<Vec<Cell>>,
-    guard_coord: Coord,
-    guard_direction: Direction,
-    previous_states: HashSet<(Coord, Direction)>,
+use std::iter;
+
+use crate::utils::{
+    bit_grid::BitGrid,
+    coord::Coord,
+    cycle::brent,
+    direction::Direction,
+    grid::Grid,
+    parse::{grid, raise, ParseError},
+};
+
+struct Lab {
+    cells: Grid<Cell>,
+    guard: Guard,
 }
-impl Grid {
-    fn new(cells: Vec<Vec<Cell>>, guard_coord: Coord, guard_direction: Direction) -> Grid {
-        Grid {
-            cells,
-            guard_coord,
-            guard_direction,
-            previous_states: HashSet::new(),
-        }
-    }
-    
-    fn step(&mut self) -> State {
-        let next_coord = self.guard_coord + match self.guard_direction {
-            Direction::Up => Coord(0, -1),
-            Direction::Down => Coord(0, 1),
-            Direction::Left => Coord(-1, 0),
-            Direction::Right => Coord(1, 0),
-        };
-
-        let moved_out = match self.get_cell(next_coord) {
-            Some(Cell::Unvisited) | Some(Cell::Visited) => {
-                self.guard_coord = next_coord;
-                self.cells[next_coord.1 as usize][next_coord.0 as usize] = Cell::Visited;
-                false
-            },
-            Some(Cell::Obstacle) => {
-                self.guard_direction = self.guard_direction.turn();
-                false
-            },
-            None => true,
-        };
-
-        if moved_out {
-            State::Out
-        } else if self.previous_states.contains(&(self.guard_coord, self.guard_direction)) {
-            State::Loop
-        } else {
-            self.previous_states.insert((self.guard_coord, self.guard_direction));
-            State::In
-        }
+impl Lab {
+    fn new(cells: Grid<Cell>, guard: Guard) -> Lab {
+        Lab { cells, guard }
     }
 
-    fn get_cell(&self, coord: Coord) -> Option<&Cell> {
-        if coord.0 < 0 || coord.1 < 0 {
-            return None;
+    /// Where the guard is after their next move, or `None` once they leave the lab.
+    fn step(&self, guard: &Guard, extra_obstacle: Option<Coord>) -> Option<Guard> {
+        let next_coord = guard.coord + guard.direction.delta();
+
+        if extra_obstacle == Some(next_coord) {
+            return Some(Guard::new(guard.coord, guard.direction.right()));
         }
 
-        self.cells.get(coord.1 as usize).and_then(|row| row.get(coord.0 as usize))
+        match self.cells.get(next_coord)? {
+            Cell::Open => Some(Guard::new(next_coord, guard.direction)),
+            Cell::Obstacle => Some(Guard::new(guard.coord, guard.direction.right())),
+        }
     }
 
-    fn count_visited(&self) -> usize {
-        self.cells
-            .iter()
-            .fold(0, |sum, row| sum + row
-                .iter()
-                .fold(0, |sum, cell| match cell {
-                    Cell::Visited => sum + 1,
-                    _ => sum,
-                })
-            )
+    fn patrol(&self) -> impl Iterator<Item = Guard> + '_ {
+        iter::successors(Some(self.guard), |guard| self.step(guard, None))
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-struct Coord(isize, isize);
-impl Add for Coord {
-    type Output = Coord;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Coord(self.0 + rhs.0, self.1 + rhs.1)
-    }    
+#[derive(Clone, Copy, PartialEq)]
+struct Guard {
+    coord: Coord,
+    direction: Direction,
+}
+impl Guard {
+    fn new(coord: Coord, direction: Direction) -> Guard {
+        Guard { coord, direction }
+    }
 }
 
 #[derive(Clone, PartialEq)]
 enum Cell {
-    Unvisited,
-    Visited,
+    Open,
     Obstacle,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-impl Direction {
-    fn from(cell: char) -> Direction {
-        match cell {
-            '^' => Direction::Up,
-            'v' => Direction::Down,
-            '<' => Direction::Left,
-            '>' => Direction::Right,
-            _ => unreachable!(),
-        }
-    }
-
-    fn turn(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
-    }
-}
-
-#[derive(PartialEq)]
-enum State {
-    In,
-    Out,
-    Loop
-}
-
 pub fn solve_part_one(input: &str) -> usize {
-    let mut grid = get_grid(input);
+    let lab = get_lab(input).unwrap_or_else(raise!());
 
-    loop {
-        if grid.step() == State::Out {
-            break;
-        }
+    let mut visited = BitGrid::like(&lab.cells);
+
+    for guard in lab.patrol() {
+        visited.insert(guard.coord);
     }
 
-    grid.count_visited()
+    visited.count()
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let grid = get_grid(input);
-
-    let mut obstacle_count = 0;
-
-    for (y, row) in grid.cells.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            if Coord(x as isize, y as isize) == grid.guard_coord || *cell == Cell::Obstacle {
-                continue;
-            }
-
-            let mut modified_grid = grid.clone();
-            modified_grid.cells[y][x] = Cell::Obstacle;
-
-            loop {
-                match modified_grid.step() {
-                    State::Out => break,
-                    State::Loop => {
-                        obstacle_count += 1;
-                        break;
-                    },
-                    State::In => (),
-                }
-            }
-        }
-    }
+    let lab = get_lab(input).unwrap_or_else(raise!());
 
-    obstacle_count
+    lab.cells
+        .iter()
+        .filter(|(coord, cell)| *coord != lab.guard.coord && **cell == Cell::Open)
+        .filter(|(coord, _)| brent(&lab.guard, |guard| lab.step(guard, Some(*coord))).is_some())
+        .count()
 }
 
-fn get_grid(input: &str) -> Grid {
-    let mut guard_coord: Option<Coord> = None;
-    let mut guard_direction: Option<Direction> = None;
-
-    let cells = input
-        .lines()
-        .enumerate()
-        .map(|(y, row)| row
-            .chars()
-            .enumerate()
-            .map(|(x, cell)| match cell {
-                '.' => Cell::Unvisited,
-                '^' | 'v' | '<' | '>' => {
-                    guard_coord = Some(Coord(x as isize, y as isize));
-                    guard_direction = Some(Direction::from(cell));
-                    Cell::Visited
-                },
-                '#' => Cell::Obstacle,
-                _ => unreachable!(),
-            })
-            .collect()
-        )
-        .collect();
-
-    Grid::new(cells, guard_coord.unwrap(), guard_direction.unwrap())
+fn get_lab(input: &str) -> Result<Lab, ParseError> {
+    let map = grid(input, |cell| matches!(cell, '.' | '#' | '^' | 'v' | '<' | '>').then_some(cell))?;
+
+    let guard_coord = map
+        .position(|cell| matches!(cell, '^' | 'v' | '<' | '>'))
+        .ok_or_else(|| ParseError::at(input, input, "a guard marked ^, v, < or >"))?;
+    let guard_direction = Direction::try_from(*map.get(guard_coord).unwrap()).unwrap();
+
+    let cells = map.map(|cell| match cell {
+        '#' => Cell::Obstacle,
+        _ => Cell::Open,
+    });
+
+    Ok(Lab::new(cells, Guard::new(guard_coord, guard_direction)))
 }
 
 #[cfg(test)]
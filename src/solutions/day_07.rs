@@ -1,5 +1,7 @@
 use itertools::{repeat_n, Itertools};
 
+use crate::utils::parse::{each_line, numbers, raise, ParseError, WHITESPACE};
+
 #[derive(Clone, Debug)]
 enum Op {
     Add,
@@ -52,7 +54,7 @@ impl Equation for (usize, Vec<usize>) {
 }
 
 pub fn solve_part_one(input: &str) -> usize {
-    let equations = get_equations(input);
+    let equations = get_equations(input).unwrap_or_else(raise!());
     let ops = Op::ops();
 
     equations.into_iter().fold(0, |sum, equation| {
@@ -65,7 +67,7 @@ pub fn solve_part_one(input: &str) -> usize {
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let equations = get_equations(input);
+    let equations = get_equations(input).unwrap_or_else(raise!());
     let ops = Op::ops_all();
 
     equations.into_iter().fold(0, |sum, equation| {
@@ -77,21 +79,20 @@ pub fn solve_part_two(input: &str) -> usize {
     })
 }
 
-fn get_equations(input: &str) -> Vec<(usize, Vec<usize>)> {
-    input
-        .lines()
-        .map(|equation| {
-            let (result, operands) = equation.split(": ").collect_tuple().unwrap();
-
-            let result = result.parse().unwrap();
-            let operands = operands
-                .split_whitespace()
-                .map(|operand| operand.parse().unwrap())
-                .collect();
-
-            (result, operands)
-        })
-        .collect()
+fn get_equations(input: &str) -> Result<Vec<(usize, Vec<usize>)>, ParseError> {
+    each_line(input, |equation| {
+        let (result, operands) = equation
+            .split_once(": ")
+            .ok_or_else(|| ParseError::at(equation, equation, "a result and operands separated by \": \""))?;
+
+        let result = result
+            .parse()
+            .map_err(|_| ParseError::at(equation, result, "a test value"))?;
+        let operands = numbers(operands, WHITESPACE)
+            .map_err(|err| err.within(equation, operands))?;
+
+        Ok((result, operands))
+    })
 }
 
 #[cfg(test)]
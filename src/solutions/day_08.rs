@@ -1,10 +1,12 @@
-use std::{collections::{HashMap, HashSet}, ops::{Add, Sub}};
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
+use crate::utils::{coord::Coord, parse::{grid, raise, ParseError}};
+
 #[derive(Debug)]
 struct Map {
-    bounds: (isize, isize),
+    bounds: Coord,
     antennae: HashMap<char, Vec<Coord>>,
 }
 impl Map {
@@ -13,10 +15,10 @@ impl Map {
 
         for (_frequency, coords) in self.antennae.iter() {
             for (coord_a, coord_b) in coords.iter().tuple_combinations() {
-                let delta = coord_a - coord_b;
+                let delta = *coord_a - *coord_b;
 
-                let antinode_a = coord_a + &delta;
-                let antinode_b = coord_b - &delta;
+                let antinode_a = *coord_a + delta;
+                let antinode_b = *coord_b - delta;
 
                 if self.is_within(&antinode_a) {
                     antinodes.insert(antinode_a);
@@ -35,28 +37,28 @@ impl Map {
 
         for (_frequency, coords) in self.antennae.iter() {
             for (coord_a, coord_b) in coords.iter().tuple_combinations() {
-                let delta = coord_a - coord_b;
+                let delta = *coord_a - *coord_b;
 
-                antinodes.insert(coord_a.clone());
-                antinodes.insert(coord_b.clone());
+                antinodes.insert(*coord_a);
+                antinodes.insert(*coord_b);
 
-                let mut next_antinode = coord_a.clone();
+                let mut next_antinode = *coord_a;
 
                 loop {
-                    next_antinode = &next_antinode + &delta;
+                    next_antinode += delta;
                     if self.is_within(&next_antinode) {
-                        antinodes.insert(next_antinode.clone());
+                        antinodes.insert(next_antinode);
                     } else {
                         break;
                     }
                 }
 
-                let mut next_antinode = coord_b.clone();
+                let mut next_antinode = *coord_b;
 
                 loop {
-                    next_antinode = &next_antinode - &delta;
+                    next_antinode -= delta;
                     if self.is_within(&next_antinode) {
-                        antinodes.insert(next_antinode.clone());
+                        antinodes.insert(next_antinode);
                     } else {
                         break;
                     }
@@ -68,55 +70,36 @@ impl Map {
     }
 
     fn is_within(&self, coord: &Coord) -> bool {
-        coord.0 >= 0 && coord.1 >= 0 && coord.0 < self.bounds.0 && coord.1 < self.bounds.1
-    }
-}
-
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Coord(isize, isize);
-impl Add for &Coord {
-    type Output = Coord;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Coord(self.0 + rhs.0, self.1 + rhs.1)
-    }    
-}
-impl Sub for &Coord {
-    type Output = Coord;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Coord(self.0 - rhs.0, self.1 - rhs.1)
+        coord.x >= 0 && coord.y >= 0 && coord.x < self.bounds.x && coord.y < self.bounds.y
     }
 }
 
 pub fn solve_part_one(input: &str) -> usize {
-    let map = get_map(input);
+    let map = get_map(input).unwrap_or_else(raise!());
     let antinodes = map.get_antinodes();
     antinodes.len()
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let map = get_map(input);
+    let map = get_map(input).unwrap_or_else(raise!());
     let antinodes = map.get_antinodes_with_resonance();
     antinodes.len()
 }
 
-fn get_map(input: &str) -> Map {
-    let map: Vec<Vec<char>> = input.lines().map(|row| row.chars().collect()).collect();
+fn get_map(input: &str) -> Result<Map, ParseError> {
+    let map = grid(input, |cell| (cell == '.' || cell.is_ascii_alphanumeric()).then_some(cell))?;
 
-    let bounds = (map[0].len() as isize, map.len() as isize);
+    let bounds = Coord::new(map.width() as isize, map.height() as isize);
     let mut antennae = HashMap::new();
 
-    for (y, row) in map.into_iter().enumerate() {
-        for (x, cell) in row.into_iter().enumerate() {
-            if cell != '.' {
-                let coords: &mut Vec<Coord> = antennae.entry(cell).or_default();
-                coords.push(Coord(x as isize, y as isize));
-            }
+    for (coord, cell) in map.iter() {
+        if *cell != '.' {
+            let coords: &mut Vec<Coord> = antennae.entry(*cell).or_default();
+            coords.push(coord);
         }
     }
 
-    Map { bounds, antennae }
+    Ok(Map { bounds, antennae })
 }
 
 #[cfg(test)]
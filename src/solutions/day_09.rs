@@ -1,3 +1,5 @@
+use crate::utils::parse::{raise, ParseError};
+
 #[derive(Clone, Debug)]
 enum Block {
     File(File),
@@ -191,22 +193,24 @@ impl Disk for Vec<Block> {
 }
 
 pub fn solve_part_one(input: &str) -> usize {
-    let mut disk = get_disk(input);
+    let mut disk = get_disk(input).unwrap_or_else(raise!());
     disk.compact();
     disk.get_checksum()
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let mut disk = get_disk(input);
+    let mut disk = get_disk(input).unwrap_or_else(raise!());
     disk.compact_two();
     disk.get_checksum()
 }
 
-fn get_disk(input: &str) -> Vec<Block> {
+fn get_disk(input: &str) -> Result<Vec<Block>, ParseError> {
     let mut blocks = Vec::new();
 
-    for (index, length) in input.trim().char_indices() {
-        let length = length.to_string().parse().unwrap();
+    for (index, length) in input.trim_end().char_indices() {
+        let length = length
+            .to_digit(10)
+            .ok_or_else(|| ParseError::new(1, index + 1, length, "a digit"))? as usize;
 
         if length == 0 {
             continue;
@@ -220,7 +224,7 @@ fn get_disk(input: &str) -> Vec<Block> {
         }
     }
 
-    blocks
+    Ok(blocks)
 }
 
 #[cfg(test)]
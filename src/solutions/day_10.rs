@@ -1,37 +1,14 @@
-use std::{collections::HashSet, ops::Add};
+use std::mem;
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Coord(isize, isize);
-impl Coord {
-    fn new(x: usize, y: usize) -> Coord {
-        Coord(x as isize, y as isize)
-    }
-
-    fn x(&self) -> usize {
-        self.0 as usize
-    }
-
-    fn y(&self) -> usize {
-        self.1 as usize
-    }
-}
-impl Add for &Coord {
-    type Output = Coord;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Coord(self.0 + rhs.0, self.1 + rhs.1)
-    }    
-}
+use crate::utils::{bit_grid::BitGrid, coord::Coord, grid::Grid, parse::{grid, raise, ParseError}};
 
 pub fn solve_part_one(input: &str) -> usize {
-    let map = get_map(input);
+    let map = get_map(input).unwrap_or_else(raise!());
     let mut sum = 0;
 
-    for (y, row) in map.iter().enumerate() {
-        for (x, height) in row.iter().enumerate() {
-            if *height == 0 {
-                sum += get_score(&map, Coord::new(x, y));
-            }
+    for (coord, height) in map.iter() {
+        if *height == 0 {
+            sum += get_score(&map, coord);
         }
     }
 
@@ -39,53 +16,45 @@ pub fn solve_part_one(input: &str) -> usize {
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let map = get_map(input);
+    let map = get_map(input).unwrap_or_else(raise!());
     let mut sum = 0;
 
-    for (y, row) in map.iter().enumerate() {
-        for (x, height) in row.iter().enumerate() {
-            if *height == 0 {
-                sum += get_distinct_score(&map, Coord::new(x, y));
-            }
+    for (coord, height) in map.iter() {
+        if *height == 0 {
+            sum += get_distinct_score(&map, coord);
         }
     }
 
     sum
 }
 
-fn get_map(input: &str) -> Vec<Vec<u8>> {
-    input
-        .lines()
-        .map(|row| row
-            .chars()
-            .map(|height| height.to_string().parse().unwrap())
-            .collect()
-        )
-        .collect()
+fn get_map(input: &str) -> Result<Grid<u8>, ParseError> {
+    grid(input, |height| height.to_digit(10).map(|height| height as u8))
 }
 
-fn get_score(map: &[Vec<u8>], trailhead: Coord) -> usize {
-    let mut current_ends = HashSet::new();
+fn get_score(map: &Grid<u8>, trailhead: Coord) -> usize {
+    let mut current_ends = BitGrid::like(map);
+    let mut new_ends = BitGrid::like(map);
     current_ends.insert(trailhead);
 
     for height in 1..=9 {
-        let mut new_ends = HashSet::new();
+        new_ends.clear();
 
         for end in current_ends.iter() {
-            for adj in get_adjacent(map, end).into_iter() {
-                if map[adj.y()][adj.x()] == height {
+            for adj in get_adjacent(map, &end).into_iter() {
+                if map.get(adj) == Some(&height) {
                     new_ends.insert(adj);
                 }
             }
         }
 
-        current_ends = new_ends;
+        mem::swap(&mut current_ends, &mut new_ends);
     }
 
-    current_ends.len()
+    current_ends.count()
 }
 
-fn get_distinct_score(map: &[Vec<u8>], trailhead: Coord) -> usize {
+fn get_distinct_score(map: &Grid<u8>, trailhead: Coord) -> usize {
     let mut current_ends = vec![trailhead];
 
     for height in 1..=9 {
@@ -93,7 +62,7 @@ fn get_distinct_score(map: &[Vec<u8>], trailhead: Coord) -> usize {
 
         for end in current_ends.iter() {
             for adj in get_adjacent(map, end).into_iter() {
-                if map[adj.y()][adj.x()] == height {
+                if map.get(adj) == Some(&height) {
                     new_ends.push(adj);
                 }
             }
@@ -105,23 +74,8 @@ fn get_distinct_score(map: &[Vec<u8>], trailhead: Coord) -> usize {
     current_ends.len()
 }
 
-fn get_adjacent(map: &[Vec<u8>], coord: &Coord) -> Vec<Coord> {
-    let mut adjacent = Vec::new();
-
-    if coord.x() > 0 {
-        adjacent.push(coord + &Coord(-1, 0));
-    }
-    if coord.y() > 0 {
-        adjacent.push(coord + &Coord(0, -1));
-    }
-    if (coord.x()) < map[coord.y()].len() - 1 {
-        adjacent.push(coord + &Coord(1, 0));
-    }
-    if (coord.y()) < map.len() - 1 {
-        adjacent.push(coord + &Coord(0, 1));
-    }
-    
-    adjacent
+fn get_adjacent(map: &Grid<u8>, coord: &Coord) -> Vec<Coord> {
+    coord.neighbours().filter(|adj| map.contains(*adj)).collect()
 }
 
 #[cfg(test)]
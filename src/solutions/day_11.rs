@@ -1,55 +1,48 @@
-use std::collections::HashMap;
+use crate::utils::{memo::Memo, parse::{numbers, raise, ParseError, WHITESPACE}};
 
 const COUNT: usize = 25;
 const COUNT_TWO: usize = 75;
 const MULTIPLIER: usize = 2024;
 
-type Cache = HashMap<(usize, usize), usize>;
-
 pub fn solve_part_one(input: &str) -> usize {
-    let stones = get_initial_stones(input);
-    let mut cache: Cache = HashMap::new();
+    let stones = get_initial_stones(input).unwrap_or_else(raise!());
+    let mut memo = Memo::new("stone counts");
 
-    stones.into_iter().map(|stone| get_count(stone, COUNT, &mut cache)).sum()
+    stones.into_iter().map(|stone| get_count(stone, COUNT, &mut memo)).sum()
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let stones = get_initial_stones(input);
-    let mut cache: Cache = HashMap::new();
+    let stones = get_initial_stones(input).unwrap_or_else(raise!());
+    let mut memo = Memo::new("stone counts");
 
-    stones.into_iter().map(|stone| get_count(stone, COUNT_TWO, &mut cache)).sum()
+    stones.into_iter().map(|stone| get_count(stone, COUNT_TWO, &mut memo)).sum()
 }
 
-fn get_initial_stones(input: &str) -> Vec<usize> {
-    input.split_whitespace().map(|stone| stone.parse().unwrap()).collect()
+fn get_initial_stones(input: &str) -> Result<Vec<usize>, ParseError> {
+    numbers(input, WHITESPACE)
 }
 
-fn get_count(stone: usize, depth: usize, cache: &mut Cache) -> usize {
-    if let Some(cached_count) = cache.get(&(stone, depth)) {
-        return *cached_count;
-    }
-
-    let new_stones = if stone == 0 {
-        vec!(1)
-    } else {
-        let stone_string = stone.to_string();
-
-        if stone_string.len() % 2 == 0 {
-            let (stone_a, stone_b) = stone_string.split_at(stone_string.len() / 2);
-            vec!(stone_a.parse().unwrap(), stone_b.parse().unwrap())
+fn get_count(stone: usize, depth: usize, memo: &mut Memo<(usize, usize), usize>) -> usize {
+    memo.get_or_insert_with((stone, depth), |memo| {
+        let new_stones = if stone == 0 {
+            vec!(1)
         } else {
-            vec!(stone * MULTIPLIER)
+            let stone_string = stone.to_string();
+
+            if stone_string.len() % 2 == 0 {
+                let (stone_a, stone_b) = stone_string.split_at(stone_string.len() / 2);
+                vec!(stone_a.parse().unwrap(), stone_b.parse().unwrap())
+            } else {
+                vec!(stone * MULTIPLIER)
+            }
+        };
+
+        if depth == 1 {
+            new_stones.len()
+        } else {
+            new_stones.into_iter().map(|stone| get_count(stone, depth - 1, memo)).sum()
         }
-    };
-
-    let count = if depth == 1 {
-        new_stones.len()
-    } else {
-        new_stones.into_iter().map(|stone| get_count(stone, depth - 1, cache)).sum()
-    };
-
-    cache.insert((stone, depth), count);
-    count
+    })
 }
 
 #[cfg(test)]
@@ -1,89 +1,102 @@
-use itertools::Itertools;
-use nalgebra::{Matrix2, Matrix2x1};
+use crate::utils::{
+    linear::{solve, Solution},
+    number_theory::extended_gcd,
+    parse::{raise, scan, sections, ParseError},
+};
+
+const PRIZE_OFFSET: i128 = 10000000000000;
 
 struct Machine {
-    a: (f64, f64),
-    b: (f64, f64),
-    prize: (f64, f64),
+    a: (i128, i128),
+    b: (i128, i128),
+    prize: (i128, i128),
 }
 
-#[allow(non_snake_case)]
 pub fn solve_part_one(input: &str) -> usize {
-    get_machines(input).fold(0, |sum, machine| {
-        let A = Matrix2::new(
-            machine.a.0, machine.b.0,
-            machine.a.1, machine.b.1,
-        );
-        let B = Matrix2x1::new(
-            machine.prize.0,
-            machine.prize.1,
-        );
-
-        let X = A.try_inverse().unwrap() * B;
-
-        if is_int(X[0]) && is_int(X[1]) {
-            sum + 3 * (X[0].round() as usize) + (X[1].round() as usize)
-        } else {
-            sum
-        }
-    })
+    get_machines(input).unwrap_or_else(raise!()).iter().filter_map(|machine| get_cost(machine, 0)).sum()
 }
 
-#[allow(non_snake_case)]
 pub fn solve_part_two(input: &str) -> usize {
-    get_machines(input).fold(0, |sum, machine| {
-        let px = machine.prize.0 + 10000000000000.0;
-        let py = machine.prize.1 + 10000000000000.0;
-        let ax = machine.a.0;
-        let ay = machine.a.1;
-        let bx = machine.b.0;
-        let by = machine.b.1;
-
-        let b = (py * ax - px * ay) / (by * ax - bx * ay);
-        let a = ( px - b * bx) / ax;
-
-        let a_rounded = a.round();
-        let b_rounded = b.round();
-
-        if a_rounded * ax + b_rounded * bx == px && a_rounded * ay + b_rounded * by == py {
-            sum + 3 * (a_rounded as usize) + (b_rounded as usize)
-        } else {
-            sum
+    get_machines(input).unwrap_or_else(raise!()).iter().filter_map(|machine| get_cost(machine, PRIZE_OFFSET)).sum()
+}
+
+/// The tokens needed to win the prize, if it can be reached with whole button presses.
+fn get_cost(machine: &Machine, offset: i128) -> Option<usize> {
+    let coefficients = [
+        vec![machine.a.0, machine.b.0],
+        vec![machine.a.1, machine.b.1],
+    ];
+    let prize = [machine.prize.0 + offset, machine.prize.1 + offset];
+
+    match solve(&coefficients, &prize) {
+        Solution::Unique(presses) => {
+            let a = usize::try_from(presses[0].to_integer()?).ok()?;
+            let b = usize::try_from(presses[1].to_integer()?).ok()?;
+
+            Some(3 * a + b)
+        }
+        Solution::None => None,
+        Solution::Infinite if (machine.a.0, machine.b.0) != (0, 0) => {
+            get_collinear_cost(machine.a.0, machine.b.0, prize[0])
         }
-    })
+        Solution::Infinite => get_collinear_cost(machine.a.1, machine.b.1, prize[1]),
+    }
 }
 
-fn get_machines(input: &str) -> impl Iterator<Item = Machine> + use<'_> {
-    input
-        .split("\n\n")
-        .map(|machine| {
-            let (a, b, prize) = machine.lines().collect_tuple().unwrap();
-
-            let a = a
-                .replace("Button A: X+", "")
-                .split(", Y+")
-                .map(|d| d.parse().unwrap())
-                .collect_tuple().unwrap();
-
-            let b = b
-                .replace("Button B: X+", "")
-                .split(", Y+")
-                .map(|d| d.parse().unwrap())
-                .collect_tuple().unwrap();
-
-            let prize = prize
-                .replace("Prize: X=", "")
-                .split(", Y=")
-                .map(|d| d.parse().unwrap())
-                .collect_tuple().unwrap();
-
-            Machine { a, b, prize }
-        })
+/// The cheapest whole presses when both buttons move the claw along the line to the prize, which
+/// leaves the single equation `a * presses_a + b * presses_b = prize`.
+fn get_collinear_cost(a: i128, b: i128, prize: i128) -> Option<usize> {
+    if (a, b) == (0, 0) {
+        return (prize == 0).then_some(0);
+    }
+
+    let (g, x, y) = extended_gcd(a, b)?;
+    if prize % g != 0 {
+        return None;
+    }
+
+    // Every integer solution is `(a_0 + k * step_a, b_0 - k * step_b)` for some `k`, so limit `k`
+    // to where both counts are non-negative.
+    let (a_0, b_0) = (x * (prize / g), y * (prize / g));
+    let (step_a, step_b) = (b / g, a / g);
+    let mut low: Option<i128> = None;
+    let mut high: Option<i128> = None;
+
+    for (start, step) in [(a_0, step_a), (b_0, -step_b)] {
+        match step.signum() {
+            1 => low = Some(low.map_or(i128::MIN, |low| low).max(-start.div_euclid(step))),
+            -1 => high = Some(high.map_or(i128::MAX, |high| high).min(start.div_euclid(-step))),
+            _ if start < 0 => return None,
+            _ => (),
+        }
+    }
+
+    // The cost changes by the same amount with each step of `k`, so the cheapest is at one end.
+    let k = match (3 * step_a - step_b).signum() {
+        1 => low?,
+        -1 => high?,
+        _ => low.or(high)?,
+    };
+    if low.is_some_and(|low| k < low) || high.is_some_and(|high| k > high) {
+        return None;
+    }
+
+    usize::try_from(3 * (a_0 + k * step_a) + b_0 - k * step_b).ok()
 }
 
-fn is_int(n: f64) -> bool {
-    let fract = n.fract();
-    fract < 1e-10 || (fract - 1.0).abs() < 1e-10
+fn get_machines(input: &str) -> Result<Vec<Machine>, ParseError> {
+    sections(input)
+        .map(|machine| get_machine(machine).map_err(|err| err.within(input, machine)))
+        .collect()
+}
+
+fn get_machine(machine: &str) -> Result<Machine, ParseError> {
+    let (ax, ay, bx, by, px, py) = scan!(
+        machine,
+        "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}" => i128, i128, i128, i128, i128, i128
+    )?;
+
+    Ok(Machine { a: (ax, ay), b: (bx, by), prize: (px, py) })
 }
 
 #[cfg(test)]
@@ -114,4 +127,36 @@ mod tests {
 
         assert_eq!(solve_part_one(INPUT), expected);
     }
+
+    #[test]
+    fn part_two() {
+        let expected = 875318608908;
+
+        assert_eq!(solve_part_two(INPUT), expected);
+    }
+
+    #[test]
+    fn collinear() {
+        let input = "\
+            Button A: X+4, Y+4\n\
+            Button B: X+1, Y+1\n\
+            Prize: X=8, Y=8\n\
+            \n\
+            Button A: X+6, Y+6\n\
+            Button B: X+4, Y+4\n\
+            Prize: X=14, Y=14\n\
+            \n\
+            Button A: X+6, Y+6\n\
+            Button B: X+4, Y+4\n\
+            Prize: X=7, Y=7\n\
+            \n\
+            Button A: X+0, Y+3\n\
+            Button B: X+0, Y+2\n\
+            Prize: X=0, Y=7\n\
+        ";
+
+        let expected = 16;
+
+        assert_eq!(solve_part_one(input), expected);
+    }
 }
@@ -1,5 +1,8 @@
-use itertools::Itertools;
-use std::ops::{Add, Mul, Rem};
+use crate::utils::{
+    coord::Vector,
+    number_theory::crt,
+    parse::{each_line, raise, scan, ParseError},
+};
 
 const WIDTH: isize = 101;
 const HEIGHT: isize = 103;
@@ -16,49 +19,8 @@ impl Robot {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-struct Vector {
-    x: isize,
-    y: isize,
-}
-impl Vector {
-    fn new(x: isize, y: isize) -> Vector {
-        Vector { x, y }
-    }
-}
-impl Add for Vector {
-    type Output = Vector;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Vector {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-impl Mul<isize> for Vector {
-    type Output = Vector;
-
-    fn mul(self, rhs: isize) -> Self::Output {
-        Vector {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
-}
-impl Rem for Vector {
-    type Output = Vector;
-
-    fn rem(self, rhs: Self) -> Self::Output {
-        Vector {
-            x: self.x.rem_euclid(rhs.x),
-            y: self.y.rem_euclid(rhs.y),
-        }
-    }
-}
-
 pub fn solve_part_one(input: &str) -> usize {
-    let mut robots = get_robots(input);
+    let mut robots = get_robots(input).unwrap_or_else(raise!());
     let seconds = 100;
     
     for robot in robots.iter_mut() {
@@ -69,48 +31,27 @@ pub fn solve_part_one(input: &str) -> usize {
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    let mut robots = get_robots(input);
-    
-    let mut n = 0;
-    loop {
-        for robot in robots.iter_mut() {
-            robot.p = (robot.p + robot.v) % BOUNDS;
-        }
-        n += 1;
-
-        if robots.iter().map(|r| r.p).all_unique() {
-            print_grid(&robots);
-            break;
-        }
-    }
-    
-    n
+    let robots = get_robots(input).unwrap_or_else(raise!());
+
+    // Columns repeat every WIDTH seconds and rows every HEIGHT seconds, so find when each axis is
+    // most bunched up and combine the two.
+    let x_time = get_tightest_time(&robots, WIDTH, |p| p.x);
+    let y_time = get_tightest_time(&robots, HEIGHT, |p| p.y);
+    let (seconds, _) = crt(&[(x_time as i64, WIDTH as i64), (y_time as i64, HEIGHT as i64)])
+        .expect("the width and height are coprime");
+
+    seconds as usize
 }
 
-fn get_robots(input: &str) -> Vec<Robot> {
-    input
-        .lines()
-        .map(|robot| {
-            let (p, v) = robot.split_whitespace().collect_tuple().unwrap();
-
-            let (px, py) = p
-                .replace("p=", "")
-                .split(",")
-                .map(|p| p.parse().unwrap())
-                .collect_tuple().unwrap();
-
-            let (vx, vy) = v
-                .replace("v=", "")
-                .split(",")
-                .map(|v| v.parse().unwrap())
-                .collect_tuple().unwrap();
-
-            Robot::new(
-                Vector::new(px, py),
-                Vector::new(vx, vy),
-            )
-        })
-        .collect()
+fn get_robots(input: &str) -> Result<Vec<Robot>, ParseError> {
+    each_line(input, |robot| {
+        let (px, py, vx, vy) = scan!(robot, "p={},{} v={},{}" => isize, isize, isize, isize)?;
+
+        Ok(Robot::new(
+            Vector::new(px, py),
+            Vector::new(vx, vy),
+        ))
+    })
 }
 
 fn get_safety_factor(robots: &[Robot]) -> usize {
@@ -131,20 +72,19 @@ fn get_safety_factor(robots: &[Robot]) -> usize {
     }).product()
 }
 
-fn print_grid(robots: &[Robot]) {
-    for y in 0..HEIGHT {
-        for x in 0..WIDTH {
-            let robot_count = robots.iter().filter(|r| r.p == Vector::new(x, y)).count();
-
-            let output = if robot_count == 0 {
-                String::from("-")
-            } else {
-                robot_count.to_string()
-            };
-            print!("{output}");
-        }
-        println!();
-    }
+/// The time within one period of an axis at which the robots' positions along it vary least.
+fn get_tightest_time(robots: &[Robot], period: isize, axis: impl Fn(Vector) -> isize) -> isize {
+    (0..period)
+        .min_by_key(|seconds| {
+            let positions: Vec<isize> = robots
+                .iter()
+                .map(|robot| axis((robot.p + (robot.v * *seconds)) % BOUNDS))
+                .collect();
+            let mean = positions.iter().sum::<isize>() / positions.len() as isize;
+
+            positions.iter().map(|position| (position - mean).pow(2)).sum::<isize>()
+        })
+        .unwrap()
 }
 
 #[cfg(test)]
@@ -1,11 +1,13 @@
+use crate::utils::{coord::Coord, direction::Direction, grid::Grid, parse::{each_line, grid, raise, sections_n, ParseError}};
+
 struct Map {
-    map: Vec<Vec<Cell>>,
+    map: Grid<Cell>,
     boxes: Vec<Coord>,
     robot: Coord,
 }
 impl Map {
     fn get_cell(&self, coord: &Coord) -> Cell {
-        self.map[coord.y as usize][coord.x as usize]
+        *self.map.get(*coord).expect("robot left the map")
     }
 }
 
@@ -15,60 +17,19 @@ enum Cell {
     Wall,
 }
 
-#[derive(Clone, Copy, PartialEq)]
-struct Coord {
-    x: isize,
-    y: isize,
-}
-impl Coord {
-    fn new(x: usize, y: usize) -> Coord {
-        Coord {
-            x: x as isize,
-            y: y as isize,
-        }
-    }
-
-    fn move_one(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Up => self.y -= 1,
-            Direction::Down => self.y += 1,
-            Direction::Left => self.x -= 1,
-            Direction::Right => self.x += 1,
-        }
-    }
-
-    fn get_gps(&self) -> usize {
-        (self.x + 100 * self.y) as usize
-    }
-}
-
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-impl Direction {
-    fn new(direction: char) -> Direction {
-        match direction {
-            '^' => Direction::Up,
-            'v' => Direction::Down,
-            '<' => Direction::Left,
-            '>' => Direction::Right,
-            _ => panic!("bad direction"),
-        }
-    }
+fn get_gps(coord: &Coord) -> usize {
+    (coord.x + 100 * coord.y) as usize
 }
 
 pub fn solve_part_one(input: &str) -> usize {
-    let (mut map, directions) = get_map(input);
+    let (mut map, directions) = get_map(input).unwrap_or_else(raise!());
     
     for direction in directions.into_iter() {
         let mut pos = map.robot;
         let mut boxes_to_move = Vec::new();
 
         let can_move = loop {
-            pos.move_one(&direction);
+            pos += direction.delta();
 
             if map.boxes.contains(&pos) {
                 boxes_to_move.push(pos);
@@ -82,52 +43,39 @@ pub fn solve_part_one(input: &str) -> usize {
 
         if can_move {
             for box_to_move in boxes_to_move.into_iter() {
-                map.boxes.iter_mut()
-                    .find(|b| **b == box_to_move).unwrap()
-                    .move_one(&direction);
+                *map.boxes.iter_mut().find(|b| **b == box_to_move).unwrap() += direction.delta();
             }
 
-            map.robot.move_one(&direction);
+            map.robot += direction.delta();
         }
     }
 
-    map.boxes.iter().map(Coord::get_gps).sum()
+    map.boxes.iter().map(get_gps).sum()
 }
 
 pub fn solve_part_two(_input: &str) -> usize {
     todo!()
 }
 
-fn get_map(input: &str) -> (Map, Vec<Direction>) {
-    let (map, directions) = input.split_once("\n\n").unwrap();
-
-    let mut boxes = Vec::new();
-    let mut robot = None;
-
-    let map = map
-        .lines()
-        .enumerate()
-        .map(|(y, row)| row
-            .chars()
-            .enumerate()
-            .map(|(x, cell)| match cell {
-                '#' => Cell::Wall,
-                '.' => Cell::Space,
-                'O' => {
-                    boxes.push(Coord::new(x, y));
-                    Cell::Space
-                },
-                '@' => {
-                    robot = Some(Coord::new(x, y));
-                    Cell::Space
-                },
-                _ => panic!("bad map cell"),
-            })
-            .collect()
-        )
+fn get_map(input: &str) -> Result<(Map, Vec<Direction>), ParseError> {
+    let [warehouse, moves] = sections_n(input)?;
+
+    let map = grid(warehouse, |cell| matches!(cell, '#' | '.' | 'O' | '@').then_some(cell))
+        .map_err(|err| err.within(input, warehouse))?;
+
+    let robot = map.find(&'@').ok_or_else(|| ParseError::at(input, warehouse, "a robot marked @"))?;
+
+    let boxes = map
+        .iter()
+        .filter(|(_coord, cell)| **cell == 'O')
+        .map(|(coord, _cell)| coord)
         .collect();
 
-    let robot = robot.expect("no robot found");
+    let map = map.map(|cell| match cell {
+        '#' => Cell::Wall,
+        '.' | 'O' | '@' => Cell::Space,
+        _ => unreachable!(),
+    });
 
     let map = Map {
         map,
@@ -135,15 +83,15 @@ fn get_map(input: &str) -> (Map, Vec<Direction>) {
         robot,
     };
 
-    let directions = directions
-        .lines()
-        .flat_map(|line| line
-            .chars()
-            .map(Direction::new)
+    let directions = each_line(moves, |line| line
+        .char_indices()
+        .map(|(index, direction)| Direction::from_arrow(direction)
+            .ok_or_else(|| ParseError::new(1, index + 1, direction, "a move ^, v, < or >"))
         )
-        .collect();
+        .collect::<Result<Vec<Direction>, ParseError>>()
+    ).map_err(|err| err.within(input, moves))?;
 
-    (map, directions)
+    Ok((map, directions.into_iter().flatten().collect()))
 }
 
 #[cfg(test)]
@@ -187,4 +135,11 @@ mod tests {
 
         assert_eq!(solve_part_two(INPUT), expected);
     }
+
+    #[test]
+    fn compass_moves() {
+        let err = get_map("#####\n#.@.#\n#####\n\n<>N\n").err().unwrap();
+
+        assert_eq!((err.line, err.column, err.text.as_str()), (5, 3, "N"));
+    }
 }
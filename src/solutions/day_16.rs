@@ -1,66 +1,44 @@
-use std::collections::{HashMap, HashSet};
+use crate::utils::{
+    bit_grid::StateSet,
+    coord::Coord,
+    direction::Direction,
+    grid::Grid,
+    parse::{grid, raise, ParseError},
+    search::{dijkstra_all_dense, AllPaths, Indexed},
+};
 
 const MOVE_SCORE: usize = 1;
 const TURN_SCORE: usize = 1000;
 
 #[derive(Debug)]
 struct Maze {
-    cells: Vec<Vec<Cell>>,
+    cells: Grid<Cell>,
     start: Coord,
     end: Coord,
 }
 impl Maze {
     fn get_shortest_path(&self) -> usize {
-        let mut nodes = HashMap::new();
-        let mut unvisited = HashSet::new();
-
-        for (y, row) in self.cells.iter().enumerate() {
-            for (x, cell) in row.iter().enumerate() {
-                if let Cell::Space = cell {
-                    let pos = Coord::new(x, y);
-
-                    for heading in Direction::iter() {
-                        let distance = if self.start == pos && heading == Direction::E {
-                            Some(0)
-                        } else {
-                            None
-                        };
-
-                        let node = Node { pos, heading };
-    
-                        nodes.insert(node, distance);
-                        unvisited.insert(node);
-                    }
-                }
-            }
-        }
+        self.get_best_paths().distance().unwrap_or(0)
+    }
 
-        loop {
-            let current = unvisited.iter()
-                .cloned()
-                .map(|node| (node, nodes.get(&node).unwrap()))
-                .filter_map(|(node, distance)| distance.map(|distance| (node, distance)))
-                .min_by(|a, b| a.1.cmp(&b.1));
-
-            if let Some(current) = current {
-                if current.0.pos == self.end {
-                    break current.1;
-                }
-
-                let neighbours = self.get_neighbours(&current.0);
-                for neighbour in neighbours.into_iter() {
-                    let distance = current.1 + neighbour.1;
-
-                    if nodes.get(&neighbour.0).unwrap().is_none_or(|d| d > distance) {
-                        nodes.insert(neighbour.0, Some(distance));
-                    }
-                }
-
-                unvisited.remove(&current.0);
-            } else {
-                break 0;
-            }
+    fn count_seats(&self) -> usize {
+        let mut seats = StateSet::like(&self.cells);
+
+        for node in self.get_best_paths().nodes() {
+            seats.insert(node.pos, node.heading);
         }
+
+        seats.positions().count()
+    }
+
+    /// Every cheapest route to the end, with nodes numbered by their slot in a `StateSet`.
+    fn get_best_paths(&self) -> AllPaths<Node, Indexed<impl Fn(&Node) -> usize>> {
+        let start = Node { pos: self.start, heading: Direction::E };
+        let states = StateSet::like(&self.cells);
+        let capacity = states.capacity();
+        let index = move |node: &Node| states.index(node.pos, node.heading).expect("nodes stay inside the maze");
+
+        dijkstra_all_dense(start, capacity, index, |node| self.get_neighbours(node), |node| node.pos == self.end)
     }
 
     fn get_neighbours(&self, current: &Node) -> Vec<(Node, usize)> {
@@ -71,13 +49,8 @@ impl Maze {
 
         let forward = current.foward();
 
-        if forward.pos.x >= 0 && forward.pos.y >= 0 {
-            let x = forward.pos.x as usize;
-            let y = forward.pos.y as usize;
-
-            if let Some(Cell::Space) = self.cells.get(y).and_then(|row| row.get(x)) {
-                neighbours.push((forward, MOVE_SCORE));
-            }
+        if let Some(Cell::Space) = self.cells.get(forward.pos) {
+            neighbours.push((forward, MOVE_SCORE));
         }
 
         neighbours
@@ -90,38 +63,6 @@ enum Cell {
     Wall,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-struct Coord {
-    x: isize,
-    y: isize,
-}
-impl Coord {
-    fn new(x: usize, y: usize) -> Coord {
-        Coord {
-            x: x as isize,
-            y: y as isize,
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-enum Direction {
-    N,
-    E,
-    S,
-    W,
-}
-impl Direction {
-    fn iter() -> impl Iterator<Item = Direction> {
-        [
-            Direction::N,
-            Direction::E,
-            Direction::S,
-            Direction::W,
-        ].into_iter()
-    }
-}
-
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 struct Node {
     pos: Coord,
@@ -129,73 +70,41 @@ struct Node {
 }
 impl Node {
     fn foward(&self) -> Node {
-        match self.heading {
-            Direction::N => Node { pos: Coord { x: self.pos.x, y: self.pos.y - 1 }, heading: self.heading },
-            Direction::E => Node { pos: Coord { x: self.pos.x + 1, y: self.pos.y }, heading: self.heading },
-            Direction::S => Node { pos: Coord { x: self.pos.x, y: self.pos.y + 1 }, heading: self.heading },
-            Direction::W => Node { pos: Coord { x: self.pos.x - 1, y: self.pos.y }, heading: self.heading },
-        }
+        Node { pos: self.pos + self.heading.delta(), heading: self.heading }
     }
 
     fn left(&self) -> Node {
-        match self.heading {
-            Direction::N => Node { pos: self.pos, heading: Direction::W },
-            Direction::E => Node { pos: self.pos, heading: Direction::N },
-            Direction::S => Node { pos: self.pos, heading: Direction::E },
-            Direction::W => Node { pos: self.pos, heading: Direction::S },
-        }
+        Node { pos: self.pos, heading: self.heading.left() }
     }
 
     fn right(&self) -> Node {
-        match self.heading {
-            Direction::N => Node { pos: self.pos, heading: Direction::E },
-            Direction::E => Node { pos: self.pos, heading: Direction::S },
-            Direction::S => Node { pos: self.pos, heading: Direction::W },
-            Direction::W => Node { pos: self.pos, heading: Direction::N },
-        }
+        Node { pos: self.pos, heading: self.heading.right() }
     }
 }
 
 pub fn solve_part_one(input: &str) -> usize {
-    let maze = get_maze(input);
+    let maze = get_maze(input).unwrap_or_else(raise!());
     maze.get_shortest_path()
 }
 
 pub fn solve_part_two(input: &str) -> usize {
-    todo!()
+    let maze = get_maze(input).unwrap_or_else(raise!());
+    maze.count_seats()
 }
 
-fn get_maze(input: &str) -> Maze {
-    let mut start = None;
-    let mut end = None;
-
-    let cells = input
-        .lines()
-        .enumerate()
-        .map(|(y, row)| row
-            .chars()
-            .enumerate()
-            .map(|(x, c)| match c {
-                '#' => Cell::Wall,
-                '.' => Cell::Space,
-                'S' => {
-                    start = Some(Coord::new(x, y));
-                    Cell::Space
-                },
-                'E' => {
-                    end = Some(Coord::new(x, y));
-                    Cell::Space
-                },
-                _ => panic!("unrecognised cell {c}"),
-            })
-            .collect()
-        )
-        .collect();
-
-    let start = start.unwrap();
-    let end = end.unwrap();
-
-    Maze { cells, start, end }
+fn get_maze(input: &str) -> Result<Maze, ParseError> {
+    let map = grid(input, |c| matches!(c, '#' | '.' | 'S' | 'E').then_some(c))?;
+
+    let start = map.find(&'S').ok_or_else(|| ParseError::at(input, input, "a start tile marked S"))?;
+    let end = map.find(&'E').ok_or_else(|| ParseError::at(input, input, "an end tile marked E"))?;
+
+    let cells = map.map(|c| match c {
+        '#' => Cell::Wall,
+        '.' | 'S' | 'E' => Cell::Space,
+        _ => unreachable!(),
+    });
+
+    Ok(Maze { cells, start, end })
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
 use itertools::Itertools;
 
+use crate::utils::parse::{fields, numbers, raise, scan, sections_n, ParseError};
+
 #[derive(Clone, Debug)]
 struct Computer {
     registers: [usize; 3],
@@ -79,29 +81,29 @@ enum OpCode {
     Cdv,
 }
 impl OpCode {
-    fn from(code: &str) -> OpCode {
+    fn from(code: usize) -> OpCode {
         match code {
-            "0" => OpCode::Adv,
-            "1" => OpCode::Bxl,
-            "2" => OpCode::Bst,
-            "3" => OpCode::Jnz,
-            "4" => OpCode::Bxc,
-            "5" => OpCode::Out,
-            "6" => OpCode::Bdv,
-            "7" => OpCode::Cdv,
+            0 => OpCode::Adv,
+            1 => OpCode::Bxl,
+            2 => OpCode::Bst,
+            3 => OpCode::Jnz,
+            4 => OpCode::Bxc,
+            5 => OpCode::Out,
+            6 => OpCode::Bdv,
+            7 => OpCode::Cdv,
             _ => panic!("unrecognised op code"),
         }
     }
 }
 
 pub fn solve_part_one(input: &str) -> String {
-    let mut computer = get_computer(input);
+    let mut computer = get_computer(input).unwrap_or_else(raise!());
     let outputs = computer.run();
     outputs.into_iter().map(|output| output.to_string()).join(",")
 }
 
 pub fn solve_part_two(input: &str) -> String {
-    let computer = get_computer(input);
+    let computer = get_computer(input).unwrap_or_else(raise!());
     let mut init_a = 0;
 
     loop {
@@ -125,36 +127,26 @@ pub fn solve_part_two(input: &str) -> String {
     }
 }
 
-fn get_computer(input: &str) -> Computer {
-    let (registers, program) = input.split_once("\n\n").unwrap();
-
-    let registers = registers
-        .lines()
-        .map(|register| register
-            .split(": ")
-            .last().unwrap()
-            .parse().unwrap()
-        )
-        .collect::<Vec<usize>>()
-        .try_into().unwrap();
-
-    let program = program
-        .replace("Program: ", "")
-        .trim()
-        .split(",")
-        .chunks(2)
-        .into_iter()
-        .map(|op| {
-            let (code, operand) = op.collect_tuple().unwrap();
+fn get_computer(input: &str) -> Result<Computer, ParseError> {
+    let [registers, program] = sections_n(input)?;
+
+    let (a, b, c) = scan!(registers, "Register A: {}\nRegister B: {}\nRegister C: {}" => usize, usize, usize)
+        .map_err(|err| err.within(input, registers))?;
 
-            let code = OpCode::from(code);
-            let operand = operand.parse().unwrap();
+    let [program] = fields(program, "Program: {}").map_err(|err| err.within(input, program))?;
+    let numbers = numbers::<usize>(program, &[',']).map_err(|err| err.within(input, program))?;
 
-            Op { code, operand }
-        })
+    if numbers.len() % 2 != 0 || numbers.iter().any(|number| *number > 7) {
+        return Err(ParseError::at(input, program, "pairs of 3-bit op codes and operands"));
+    }
+
+    let program = numbers
+        .into_iter()
+        .tuples()
+        .map(|(code, operand)| Op { code: OpCode::from(code), operand })
         .collect();
 
-    Computer { registers, program }
+    Ok(Computer { registers: [a, b, c], program })
 }
 
 #[cfg(test)]
@@ -1,98 +1,41 @@
-use std::{cmp, collections::{HashMap, HashSet}};
-
-struct Grid<'a> {
+use crate::utils::{
+    bit_grid::BitGrid,
+    coord::Coord,
+    parse::{each_line, raise, ParseError},
+    search::bfs_dense,
+    union_find::UnionFind,
+};
+
+struct MemorySpace {
     size: usize,
-    blocked: &'a [Coord],
+    free: BitGrid,
 }
-impl Grid<'_> {
-    fn get_shortest_path(&self) -> Option<usize> {
-        let mut nodes = HashMap::new();
-        let mut unvisited = HashSet::new();
-
-        for x in 0..=self.size {
-            for y in 0..=self.size {
-                let node = Coord::new(x, y);
-
-                let distance = if node == Coord::new(0, 0) {
-                    Some(0)
-                } else {
-                    None
-                };
-
-                nodes.insert(node, distance);
-                unvisited.insert(node);
-            }
-        }
+impl MemorySpace {
+    fn new(size: usize, blocked: &[Coord]) -> MemorySpace {
+        let mut free = BitGrid::new(size + 1, size + 1);
+        free.fill();
 
-        loop {
-            let current = unvisited.iter()
-                .cloned()
-                .map(|node| (node, nodes.get(&node).unwrap()))
-                .filter_map(|(node, distance)| distance.map(|distance| (node, distance)))
-                .min_by(|a, b| a.1.cmp(&b.1));
-
-            if let Some(current) = current {
-                if current.0 == Coord::new(self.size, self.size) {
-                    break Some(current.1);
-                }
-
-                let neighbours = self.get_neighbours(&current.0);
-                for neighbour in neighbours.into_iter() {
-                    let distance = current.1 + 1;
-
-                    if nodes.get(&neighbour).unwrap().is_none_or(|d| d > distance) {
-                        nodes.insert(neighbour, Some(distance));
-                    }
-                }
-
-                unvisited.remove(&current.0);
-            } else {
-                break None;
-            }
+        for coord in blocked.iter() {
+            free.remove(*coord);
         }
-    }
-
-    fn get_neighbours(&self, coord: &Coord) -> Vec<Coord> {
-        let mut neighbours = Vec::new();
 
-        if coord.x > 0 {
-            neighbours.push(Coord { x: coord.x - 1, y: coord.y });
-        }
-
-        if coord.y > 0 {
-            neighbours.push(Coord { x: coord.x, y: coord.y - 1 });
-        }
-
-        if coord.x < self.size as isize {
-            neighbours.push(Coord { x: coord.x + 1, y: coord.y });
-        }
+        MemorySpace { size, free }
+    }
 
-        if coord.y < self.size as isize {
-            neighbours.push(Coord { x: coord.x, y: coord.y + 1 });
-        }
+    fn get_shortest_path(&self) -> Option<usize> {
+        let exit = Coord::new(self.size as isize, self.size as isize);
 
-        neighbours.into_iter().filter(|n| !self.blocked.contains(n)).collect()
-    }
-}
+        let index = |coord: &Coord| self.free.index(*coord).expect("paths stay inside the memory space");
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-struct Coord {
-    x: isize,
-    y: isize,
-}
-impl Coord {
-    fn from(coord: (&str, &str)) -> Coord {
-        Coord {
-            x: coord.0.parse().unwrap(),
-            y: coord.1.parse().unwrap(),
-        }
+        bfs_dense(Coord::ORIGIN, self.free.capacity(), index, |coord| self.get_neighbours(coord), |coord| *coord == exit)
+            .distance()
     }
 
-    fn new(x: usize, y: usize) -> Coord {
-        Coord {
-            x: x as isize,
-            y: y as isize,
-        }
+    fn get_neighbours(&self, coord: &Coord) -> Vec<Coord> {
+        coord
+            .neighbours()
+            .filter(|n| self.free.contains(*n))
+            .collect()
     }
 }
 
@@ -103,57 +46,68 @@ pub fn solve_part_one(input: &str) -> String {
 }
 
 fn get_shortest_path(input: &str, grid_size: usize, bytes: usize) -> usize {
-    let coords = get_coords(input);
+    let coords = get_coords(input).unwrap_or_else(raise!());
     let blocked = &coords[..bytes];
 
-    let grid = Grid { size: grid_size, blocked };
+    let memory = MemorySpace::new(grid_size, blocked);
 
-    grid.get_shortest_path().unwrap()
+    memory.get_shortest_path().unwrap()
 }
 
 pub fn solve_part_two(input: &str) -> String {
     let grid_size = 70;
     get_first_blocker(input, grid_size)
+        .expect("exit is still reachable after every byte has fallen")
+        .to_string()
 }
 
-fn get_first_blocker(input: &str, grid_size: usize) -> String {
-    let coords = get_coords(input);
+/// The byte whose fall first cuts off the exit, or `None` if the exit stays reachable.
+fn get_first_blocker(input: &str, grid_size: usize) -> Option<Coord> {
+    let coords = get_coords(input).unwrap_or_else(raise!());
+    let exit = Coord::new(grid_size as isize, grid_size as isize);
 
-    let mut d = coords.len() / 2;
-    let mut n = d;
-    let mut is_blocked_at = HashMap::new();
+    let mut memory = MemorySpace::new(grid_size, &coords);
+    let mut regions = UnionFind::new();
 
-    loop {
-        d = cmp::max(d / 2, 1);
+    for coord in memory.free.iter() {
+        for neighbour in memory.get_neighbours(&coord) {
+            regions.union(coord, neighbour);
+        }
+    }
 
-        let blocked = &coords[..=n];
-        let grid = Grid { size: grid_size, blocked };
+    if regions.connected(Coord::ORIGIN, exit) {
+        return None;
+    }
 
-        if grid.get_shortest_path().is_some() {
-            if is_blocked_at.get(&(n + 1)).is_some_and(|b| *b) {
-                let coord = coords[n + 1];
-                break format!("{},{}", coord.x, coord.y);
-            }
+    // A byte landing on a cell already blocked changes nothing, so only the first byte in each
+    // cell can clear it, and bytes off the grid never block anything.
+    let mut seen = BitGrid::new(grid_size + 1, grid_size + 1);
+    let firsts: Vec<Coord> = coords
+        .iter()
+        .filter(|coord| seen.index(**coord).is_some() && seen.insert(**coord))
+        .copied()
+        .collect();
+
+    // Clear the bytes from the last to fall until the exit is reachable, so each byte only joins
+    // regions once instead of searching again for every candidate.
+    let blocker = firsts.iter().rev().find(|coord| {
+        memory.free.insert(**coord);
+
+        for neighbour in memory.get_neighbours(coord) {
+            regions.union(**coord, neighbour);
+        }
 
-            is_blocked_at.insert(n, false);
-            n += d;
-        } else {
-            if is_blocked_at.get(&(n - 1)).is_some_and(|b| !b) {
-                let coord = coords[n];
-                break format!("{},{}", coord.x, coord.y);
-            }
+        regions.connected(Coord::ORIGIN, exit)
+    });
 
-            is_blocked_at.insert(n, true);
-            n -=d;
-        }
-    }
+    Some(*blocker.expect("exit is unreachable even with every byte cleared"))
 }
 
-fn get_coords(input: &str) -> Vec<Coord> {
-    input
-        .lines()
-        .map(|coord| Coord::from(coord.split_once(",").unwrap()))
-        .collect()
+fn get_coords(input: &str) -> Result<Vec<Coord>, ParseError> {
+    each_line(input, |coord| coord
+        .parse()
+        .map_err(|_| ParseError::at(coord, coord, "a coordinate like 3,4"))
+    )
 }
 
 #[cfg(test)]
@@ -202,8 +156,33 @@ mod tests {
     fn part_two() {
         let grid_size = 6;
 
-        let expected = "6,1";
+        let expected = Some(Coord::new(6, 1));
 
         assert_eq!(get_first_blocker(INPUT, grid_size), expected);
     }
+
+    #[test]
+    fn never_blocked() {
+        let grid_size = 6;
+
+        assert_eq!(get_first_blocker(&INPUT[..INPUT.find("6,1").unwrap()], grid_size), None);
+    }
+
+    #[test]
+    fn repeated_and_stray_bytes() {
+        let grid_size = 6;
+        let input = format!("{INPUT}3,3\n7,7\n-1,2\n");
+
+        let expected = Some(Coord::new(6, 1));
+
+        assert_eq!(get_first_blocker(&input, grid_size), expected);
+    }
+
+    #[test]
+    fn blocked_by_first_byte() {
+        let grid_size = 6;
+        let input = format!("6,6\n{INPUT}");
+
+        assert_eq!(get_first_blocker(&input, grid_size), Some(Coord::new(6, 6)));
+    }
 }
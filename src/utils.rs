@@ -0,0 +1,13 @@
+pub mod bit_grid;
+pub mod coord;
+pub mod cycle;
+pub mod dag;
+pub mod direction;
+pub mod grid;
+pub mod linear;
+pub mod memo;
+pub mod number_theory;
+pub mod parse;
+pub mod region;
+pub mod search;
+pub mod union_find;
@@ -0,0 +1,320 @@
+use super::{coord::Coord, direction::Direction, grid::Grid};
+
+/// A set of indices below a fixed length, stored as flags packed 64 to a word. Adding an index
+/// past the end panics.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Bits {
+    words: Vec<u64>,
+    len: usize,
+}
+impl Bits {
+    fn new(len: usize) -> Bits {
+        Bits { words: vec![0; len.div_ceil(64)], len }
+    }
+
+    /// The number of indices the set can hold.
+    fn capacity(&self) -> usize {
+        self.len
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        index < self.len && self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Adds an index, returning false if it was already present.
+    fn insert(&mut self, index: usize) -> bool {
+        assert!(index < self.len, "index {index} is outside a bit set of {}", self.len);
+
+        let word = &mut self.words[index / 64];
+        let mask = 1 << (index % 64);
+        let inserted = *word & mask == 0;
+
+        *word |= mask;
+        inserted
+    }
+
+    /// Removes an index, returning false if it wasn't present.
+    fn remove(&mut self, index: usize) -> bool {
+        if index >= self.len {
+            return false;
+        }
+
+        let word = &mut self.words[index / 64];
+        let mask = 1 << (index % 64);
+        let removed = *word & mask != 0;
+
+        *word &= !mask;
+        removed
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Adds every index below the length.
+    fn fill(&mut self) {
+        self.words.fill(u64::MAX);
+
+        // Keep the unused bits of the last word clear so that counting stays correct.
+        let unused = self.words.len() * 64 - self.len;
+        if unused > 0 {
+            *self.words.last_mut().unwrap() = u64::MAX >> unused;
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Overwrites this set with another of the same length without reallocating.
+    fn copy_from(&mut self, other: &Bits) {
+        assert_eq!(self.len, other.len, "copying between bit sets of different sizes");
+        self.words.copy_from_slice(&other.words);
+    }
+
+    /// The indices in the set, in increasing order.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// A set of cells in a rectangular grid, stored as one bit per cell. Lookups off the grid find
+/// nothing, but adding a cell off the grid panics.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: Bits,
+    width: usize,
+    height: usize,
+}
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        BitGrid {
+            bits: Bits::new(width * height),
+            width,
+            height,
+        }
+    }
+
+    /// An empty set the same size as `grid`.
+    pub fn like<T>(grid: &Grid<T>) -> BitGrid {
+        BitGrid::new(grid.width(), grid.height())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        self.index(coord).is_some_and(|index| self.bits.contains(index))
+    }
+
+    /// Adds a cell, returning false if it was already present.
+    pub fn insert(&mut self, coord: Coord) -> bool {
+        let index = self.index(coord).unwrap_or_else(|| panic!("{coord} is outside the grid"));
+        self.bits.insert(index)
+    }
+
+    /// Removes a cell, returning false if it wasn't present.
+    pub fn remove(&mut self, coord: Coord) -> bool {
+        self.index(coord).is_some_and(|index| self.bits.remove(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Adds every cell in the grid.
+    pub fn fill(&mut self) {
+        self.bits.fill();
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Overwrites this set with another of the same size without reallocating.
+    pub fn copy_from(&mut self, other: &BitGrid) {
+        self.bits.copy_from(&other.bits);
+    }
+
+    /// The cells in the set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.bits.iter().map(|index| Coord::new((index % self.width) as isize, (index / self.width) as isize))
+    }
+
+    /// The number of cells in the grid, which bounds every index.
+    pub fn capacity(&self) -> usize {
+        self.bits.capacity()
+    }
+
+    /// A cell's position in row-major order, for keeping other per-cell state in a plain vector.
+    pub fn index(&self, coord: Coord) -> Option<usize> {
+        let in_bounds = coord.x >= 0 && coord.y >= 0 && (coord.x as usize) < self.width && (coord.y as usize) < self.height;
+        in_bounds.then(|| coord.y as usize * self.width + coord.x as usize)
+    }
+}
+
+/// A set of positions paired with headings, such as states already visited by a walker, stored as
+/// four bits per cell.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StateSet {
+    cells: BitGrid,
+}
+impl StateSet {
+    pub fn new(width: usize, height: usize) -> StateSet {
+        StateSet { cells: BitGrid::new(width * 4, height) }
+    }
+
+    /// An empty set covering the same cells as `grid`.
+    pub fn like<T>(grid: &Grid<T>) -> StateSet {
+        StateSet::new(grid.width(), grid.height())
+    }
+
+    pub fn contains(&self, coord: Coord, direction: Direction) -> bool {
+        self.inner(coord, direction).is_some_and(|inner| self.cells.contains(inner))
+    }
+
+    /// Adds a state, returning false if it was already present.
+    pub fn insert(&mut self, coord: Coord, direction: Direction) -> bool {
+        let inner = self.inner(coord, direction).unwrap_or_else(|| panic!("{coord} is outside the grid"));
+        self.cells.insert(inner)
+    }
+
+    pub fn remove(&mut self, coord: Coord, direction: Direction) -> bool {
+        self.inner(coord, direction).is_some_and(|inner| self.cells.remove(inner))
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    pub fn count(&self) -> usize {
+        self.cells.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn copy_from(&mut self, other: &StateSet) {
+        self.cells.copy_from(&other.cells);
+    }
+
+    /// The number of states the set can hold, which bounds every index.
+    pub fn capacity(&self) -> usize {
+        self.cells.capacity()
+    }
+
+    /// A state's position in the set, which counts four per cell in row-major order.
+    pub fn index(&self, coord: Coord, direction: Direction) -> Option<usize> {
+        self.cells.index(self.inner(coord, direction)?)
+    }
+
+    /// The positions with at least one heading in the set.
+    pub fn positions(&self) -> BitGrid {
+        let mut positions = BitGrid::new(self.cells.width / 4, self.cells.height);
+        for inner in self.cells.iter() {
+            positions.insert(Coord::new(inner.x / 4, inner.y));
+        }
+
+        positions
+    }
+
+    fn inner(&self, coord: Coord, direction: Direction) -> Option<Coord> {
+        let in_bounds = coord.x >= 0 && (coord.x as usize) < self.cells.width / 4;
+        in_bounds.then(|| Coord::new(coord.x * 4 + direction.index() as isize, coord.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells() {
+        let mut cells = BitGrid::new(10, 13);
+
+        assert!(cells.insert(Coord::new(9, 12)));
+        assert!(cells.insert(Coord::new(3, 0)));
+        assert!(!cells.insert(Coord::new(3, 0)));
+        assert!(cells.contains(Coord::new(9, 12)));
+        assert!(!cells.contains(Coord::new(10, 0)));
+        assert!(!cells.contains(Coord::new(-1, 0)));
+        assert_eq!(cells.iter().collect::<Vec<_>>(), vec![Coord::new(3, 0), Coord::new(9, 12)]);
+
+        let mut copy = BitGrid::new(10, 13);
+        copy.copy_from(&cells);
+
+        assert!(copy.remove(Coord::new(3, 0)));
+        assert!(!copy.remove(Coord::new(3, 0)));
+        assert_eq!((cells.count(), copy.count()), (2, 1));
+
+        cells.fill();
+
+        assert_eq!(cells.count(), 130);
+
+        cells.clear();
+
+        assert!(cells.is_empty());
+    }
+
+    #[test]
+    fn indices() {
+        let mut bits = Bits::new(70);
+
+        assert!(bits.insert(69));
+        assert!(bits.insert(3));
+        assert!(!bits.contains(70));
+        assert!(!bits.remove(70));
+        assert_eq!(bits.iter().collect::<Vec<_>>(), vec![3, 69]);
+
+        bits.fill();
+
+        assert_eq!(bits.count(), 70);
+        assert_eq!(BitGrid::new(10, 13).index(Coord::new(9, 1)), Some(19));
+        assert_eq!(StateSet::new(5, 5).index(Coord::new(1, 1), Direction::S), Some(26));
+        assert_eq!(StateSet::new(5, 5).capacity(), 100);
+    }
+
+    #[test]
+    #[should_panic]
+    fn outside() {
+        BitGrid::new(3, 3).insert(Coord::new(3, 0));
+    }
+
+    #[test]
+    fn states() {
+        let mut states = StateSet::new(5, 5);
+
+        assert!(states.insert(Coord::new(4, 4), Direction::W));
+        assert!(states.insert(Coord::new(4, 4), Direction::N));
+        assert!(!states.insert(Coord::new(4, 4), Direction::W));
+        assert!(states.contains(Coord::new(4, 4), Direction::N));
+        assert!(!states.contains(Coord::new(4, 4), Direction::E));
+        assert!(!states.contains(Coord::new(5, 0), Direction::N));
+        assert_eq!(states.count(), 2);
+        assert_eq!(states.positions().iter().collect::<Vec<_>>(), vec![Coord::new(4, 4)]);
+    }
+}
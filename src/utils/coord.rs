@@ -0,0 +1,236 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    num::ParseIntError,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// A 2D point or vector on an integer grid, with `y` increasing downwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// The same type, for when a value is a displacement or velocity rather than a position.
+pub type Vector = Coord;
+
+const ORTHOGONAL: [Coord; 4] = [
+    Coord { x: 0, y: -1 },
+    Coord { x: 1, y: 0 },
+    Coord { x: 0, y: 1 },
+    Coord { x: -1, y: 0 },
+];
+
+const ALL: [Coord; 8] = [
+    Coord { x: 0, y: -1 },
+    Coord { x: 1, y: -1 },
+    Coord { x: 1, y: 0 },
+    Coord { x: 1, y: 1 },
+    Coord { x: 0, y: 1 },
+    Coord { x: -1, y: 1 },
+    Coord { x: -1, y: 0 },
+    Coord { x: -1, y: -1 },
+];
+
+impl Coord {
+    pub const ORIGIN: Coord = Coord { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Coord {
+        Coord { x, y }
+    }
+
+    pub fn manhattan(&self, other: Coord) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: Coord) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Wraps each component into `0..bounds`, even when negative.
+    pub fn rem_euclid(&self, bounds: Coord) -> Coord {
+        Coord::new(self.x.rem_euclid(bounds.x), self.y.rem_euclid(bounds.y))
+    }
+
+    /// The four orthogonal neighbours, clockwise from up.
+    pub fn neighbours(self) -> impl Iterator<Item = Coord> {
+        ORTHOGONAL.into_iter().map(move |delta| self + delta)
+    }
+
+    /// All eight neighbours including diagonals, clockwise from up.
+    pub fn all_neighbours(self) -> impl Iterator<Item = Coord> {
+        ALL.into_iter().map(move |delta| self + delta)
+    }
+
+    pub fn is_adjacent(&self, other: Coord) -> bool {
+        self.manhattan(other) == 1
+    }
+}
+
+/// Reading order: by row, then by column.
+impl Ord for Coord {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+impl PartialOrd for Coord {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<(isize, isize)> for Coord {
+    fn from((x, y): (isize, isize)) -> Coord {
+        Coord { x, y }
+    }
+}
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Coord::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+impl AddAssign for Coord {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+impl Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Coord::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+impl SubAssign for Coord {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+impl Neg for Coord {
+    type Output = Coord;
+
+    fn neg(self) -> Self::Output {
+        Coord::new(-self.x, -self.y)
+    }
+}
+impl Mul<isize> for Coord {
+    type Output = Coord;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Coord::new(self.x * rhs, self.y * rhs)
+    }
+}
+impl MulAssign<isize> for Coord {
+    fn mul_assign(&mut self, rhs: isize) {
+        *self = *self * rhs;
+    }
+}
+impl Div<isize> for Coord {
+    type Output = Coord;
+
+    fn div(self, rhs: isize) -> Self::Output {
+        Coord::new(self.x / rhs, self.y / rhs)
+    }
+}
+/// Euclidean remainder by component, so positions wrap around a torus of the given bounds.
+impl Rem for Coord {
+    type Output = Coord;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.rem_euclid(rhs)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseCoordError {
+    MissingComma,
+    InvalidNumber(ParseIntError),
+}
+impl fmt::Display for ParseCoordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCoordError::MissingComma => write!(f, "expected a coordinate like 3,4"),
+            ParseCoordError::InvalidNumber(err) => write!(f, "invalid coordinate: {err}"),
+        }
+    }
+}
+
+/// Parses `x,y`, allowing whitespace around either number.
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s.split_once(',').ok_or(ParseCoordError::MissingComma)?;
+
+        let x = x.trim().parse().map_err(ParseCoordError::InvalidNumber)?;
+        let y = y.trim().parse().map_err(ParseCoordError::InvalidNumber)?;
+
+        Ok(Coord { x, y })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Coord::new(2, -3);
+        let b = Coord::new(-1, 5);
+
+        assert_eq!(a + b, Coord::new(1, 2));
+        assert_eq!(a - b, Coord::new(3, -8));
+        assert_eq!(-a, Coord::new(-2, 3));
+        assert_eq!(a * 3, Coord::new(6, -9));
+        assert_eq!(b * 4 / 2, Coord::new(-2, 10));
+        assert_eq!(Coord::new(-1, 7) % Coord::new(5, 5), Coord::new(4, 2));
+    }
+
+    #[test]
+    fn distances() {
+        let a = Coord::new(1, 1);
+        let b = Coord::new(4, -1);
+
+        assert_eq!(a.manhattan(b), 5);
+        assert_eq!(a.chebyshev(b), 3);
+        assert!(a.is_adjacent(Coord::new(1, 2)));
+        assert!(!a.is_adjacent(Coord::new(2, 2)));
+    }
+
+    #[test]
+    fn neighbours() {
+        let neighbours: Vec<Coord> = Coord::ORIGIN.neighbours().collect();
+
+        assert_eq!(neighbours, ORTHOGONAL);
+        assert_eq!(Coord::ORIGIN.all_neighbours().count(), 8);
+        assert!(Coord::ORIGIN.all_neighbours().all(|n| n.chebyshev(Coord::ORIGIN) == 1));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut coords = vec![Coord::new(2, 1), Coord::new(0, 2), Coord::new(1, 1)];
+        coords.sort();
+
+        assert_eq!(coords, vec![Coord::new(1, 1), Coord::new(2, 1), Coord::new(0, 2)]);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("6,1".parse(), Ok(Coord::new(6, 1)));
+        assert_eq!(" -3, 4".parse(), Ok(Coord::new(-3, 4)));
+        assert_eq!("6".parse::<Coord>(), Err(ParseCoordError::MissingComma));
+        assert!("a,1".parse::<Coord>().is_err());
+        assert_eq!(Coord::new(6, 1).to_string(), "6,1");
+    }
+}
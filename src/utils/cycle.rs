@@ -0,0 +1,132 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Where a deterministic sequence of states starts repeating. The step function returns `None`
+/// when the simulation ends instead, in which case there is no cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first step whose state recurs.
+    pub start: usize,
+    pub length: usize,
+}
+impl Cycle {
+    /// The earliest step with the same state as step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// The state at step `n`, found by replaying no more than one pass through the cycle.
+    pub fn state_at<S: Clone>(&self, initial: &S, mut step: impl FnMut(&S) -> Option<S>, n: usize) -> S {
+        (0..self.equivalent_step(n)).fold(initial.clone(), |state, _| {
+            step(&state).expect("states before the end of a cycle have successors")
+        })
+    }
+}
+
+/// Brent's algorithm, which only compares states rather than storing them. The step function is
+/// called several times on the same states, so it must be deterministic.
+pub fn brent<S: Clone + PartialEq>(initial: &S, mut step: impl FnMut(&S) -> Option<S>) -> Option<Cycle> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial)?;
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = (0..length).try_fold(initial.clone(), |state, _| step(&state))?;
+    let mut start = 0;
+
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Every state up to the point the sequence repeats, so that later states can be looked up
+/// directly.
+#[derive(Clone, Debug)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+impl<S> History<S> {
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.cycle.equivalent_step(n)]
+    }
+
+    /// The states from the start, up to and including the last one in the cycle.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+}
+
+/// Steps until a state is seen twice, remembering each one by its hash.
+pub fn detect<S: Clone + Hash + Eq>(initial: &S, mut step: impl FnMut(&S) -> Option<S>) -> Option<History<S>> {
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial.clone()];
+
+    loop {
+        let next = step(states.last().unwrap())?;
+
+        if let Some(start) = seen.get(&next) {
+            let cycle = Cycle { start: *start, length: states.len() - start };
+            return Some(History { cycle, states });
+        }
+
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The last three digits of 2, 4, 16, 256, ... return to 16 after another 20 squarings.
+    fn square(n: &u64) -> Option<u64> {
+        Some(n * n % 1000)
+    }
+
+    #[test]
+    fn repeating() {
+        let expected = Cycle { start: 2, length: 20 };
+
+        assert_eq!(brent(&2, square), Some(expected));
+        assert_eq!(detect(&2, square).unwrap().cycle, expected);
+        assert_eq!(brent(&7, |n| Some((n + 1) % 5)), Some(Cycle { start: 1, length: 5 }));
+        assert_eq!(brent(&0, |n: &u64| (*n < 10).then_some(n + 1)), None);
+        assert!(detect(&0, |n: &u64| (*n < 10).then_some(n + 1)).is_none());
+    }
+
+    #[test]
+    fn jumping() {
+        let history = detect(&2, square).unwrap();
+        let cycle = history.cycle;
+        let steps = cycle.start + 3 * cycle.length + 5;
+
+        let mut state = 2;
+        for _ in 0..steps {
+            state = square(&state).unwrap();
+        }
+
+        assert_eq!(cycle.state_at(&2, square, steps), state);
+        assert_eq!(*history.state_at(steps), state);
+        assert_eq!(*history.state_at(1_000_000_007), cycle.state_at(&2, square, 1_000_000_007));
+        assert_eq!(history.states().len(), cycle.start + cycle.length);
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    error::Error,
+    fmt,
+    hash::Hash,
+};
+
+/// A directed graph meant to describe a partial order, where an edge from `a` to `b` means `a`
+/// comes before `b`. Nodes remember the order they were added in, which breaks ties when sorting.
+#[derive(Clone, Debug, Default)]
+pub struct Dag<N> {
+    indices: HashMap<N, usize>,
+    nodes: Vec<N>,
+    edges: Vec<Vec<usize>>,
+}
+impl<N: Copy + Eq + Hash> Dag<N> {
+    pub fn new() -> Dag<N> {
+        Dag {
+            indices: HashMap::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Dag<N> {
+        let mut dag = Dag::new();
+        for (before, after) in edges {
+            dag.add_edge(before, after);
+        }
+
+        dag
+    }
+
+    /// Adds a node with no edges, unless it is already present.
+    pub fn insert(&mut self, node: N) {
+        self.index(node);
+    }
+
+    pub fn add_edge(&mut self, before: N, after: N) {
+        let before = self.index(before);
+        let after = self.index(after);
+
+        if !self.edges[before].contains(&after) {
+            self.edges[before].push(after);
+        }
+    }
+
+    pub fn contains(&self, node: N) -> bool {
+        self.indices.contains_key(&node)
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// The nodes directly after `node`.
+    pub fn successors(&self, node: N) -> impl Iterator<Item = N> + '_ {
+        self.indices
+            .get(&node)
+            .into_iter()
+            .flat_map(|index| self.edges[*index].iter().map(|after| self.nodes[*after]))
+    }
+
+    /// The graph restricted to `nodes`, which are added in the order given.
+    pub fn subgraph(&self, nodes: &[N]) -> Dag<N> {
+        let mut subgraph = Dag::new();
+        for node in nodes {
+            subgraph.insert(*node);
+        }
+
+        for before in nodes {
+            for after in self.successors(*before) {
+                if subgraph.contains(after) {
+                    subgraph.add_edge(*before, after);
+                }
+            }
+        }
+
+        subgraph
+    }
+
+    /// Kahn's algorithm. Whenever several nodes are free to go next, the one added first wins, so
+    /// the result only depends on the insertion order.
+    pub fn topological_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        let mut incoming = vec![0; self.nodes.len()];
+        for after in self.edges.iter().flatten() {
+            incoming[*after] += 1;
+        }
+
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.nodes.len())
+            .filter(|index| incoming[*index] == 0)
+            .map(Reverse)
+            .collect();
+        let mut sorted = Vec::with_capacity(self.nodes.len());
+
+        while let Some(Reverse(index)) = ready.pop() {
+            sorted.push(self.nodes[index]);
+
+            for after in &self.edges[index] {
+                incoming[*after] -= 1;
+                if incoming[*after] == 0 {
+                    ready.push(Reverse(*after));
+                }
+            }
+        }
+
+        if sorted.len() < self.nodes.len() {
+            return Err(CycleError { nodes: self.find_cycle().expect("unsorted nodes lie on a cycle") });
+        }
+
+        Ok(sorted)
+    }
+
+    /// Some cycle in the graph, starting and ending at the same node.
+    pub fn find_cycle(&self) -> Option<Vec<N>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            Open,
+            Done,
+        }
+
+        let mut marks = vec![Mark::New; self.nodes.len()];
+
+        for root in 0..self.nodes.len() {
+            if marks[root] != Mark::New {
+                continue;
+            }
+
+            // Each entry is a node on the current path and how many of its edges have been tried.
+            let mut path = vec![(root, 0)];
+            marks[root] = Mark::Open;
+
+            while let Some((index, tried)) = path.last_mut() {
+                let index = *index;
+
+                let Some(after) = self.edges[index].get(*tried).copied() else {
+                    marks[index] = Mark::Done;
+                    path.pop();
+                    continue;
+                };
+                *tried += 1;
+
+                match marks[after] {
+                    Mark::New => {
+                        marks[after] = Mark::Open;
+                        path.push((after, 0));
+                    }
+                    Mark::Open => {
+                        let start = path.iter().position(|(node, _)| *node == after).unwrap();
+                        let mut cycle: Vec<N> = path[start..].iter().map(|(node, _)| self.nodes[*node]).collect();
+                        cycle.push(self.nodes[after]);
+
+                        return Some(cycle);
+                    }
+                    Mark::Done => (),
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Every node that can be reached from `node` by following edges, not including itself unless
+    /// it lies on a cycle.
+    pub fn descendants(&self, node: N) -> HashSet<N> {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<N> = self.successors(node).collect();
+
+        while let Some(current) = stack.pop() {
+            if seen.insert(current) {
+                stack.extend(self.successors(current));
+            }
+        }
+
+        seen
+    }
+
+    /// Whether `before` must come before `after`, directly or through other nodes.
+    pub fn precedes(&self, before: N, after: N) -> bool {
+        self.descendants(before).contains(&after)
+    }
+
+    /// Whether no edge leads from a node in `sequence` back to one earlier in it. Only edges
+    /// between nodes of the sequence count, so paths through nodes outside it are ignored.
+    pub fn respects(&self, sequence: &[N]) -> bool {
+        let positions: HashMap<N, usize> = sequence.iter().enumerate().map(|(i, node)| (*node, i)).collect();
+
+        sequence.iter().enumerate().all(|(i, before)| {
+            self.successors(*before).all(|after| positions.get(&after).is_none_or(|j| *j > i))
+        })
+    }
+
+    fn index(&mut self, node: N) -> usize {
+        if let Some(index) = self.indices.get(&node) {
+            return *index;
+        }
+
+        let index = self.nodes.len();
+        self.indices.insert(node, index);
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+
+        index
+    }
+}
+
+/// The nodes of a cycle that stopped a topological sort, starting and ending at the same node.
+#[derive(Debug, PartialEq)]
+pub struct CycleError<N> {
+    pub nodes: Vec<N>,
+}
+impl<N: fmt::Debug> fmt::Display for CycleError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nodes: Vec<String> = self.nodes.iter().map(|node| format!("{node:?}")).collect();
+        write!(f, "cycle through {}", nodes.join(" -> "))
+    }
+}
+impl<N: fmt::Debug> Error for CycleError<N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorting() {
+        let mut dag = Dag::from_edges([('c', 'a'), ('d', 'b'), ('c', 'b')]);
+        dag.insert('e');
+
+        assert_eq!(dag.topological_sort(), Ok(vec!['c', 'a', 'd', 'b', 'e']));
+        assert!(dag.respects(&['d', 'c', 'b', 'a']));
+        assert!(!dag.respects(&['b', 'c']));
+        assert!(dag.respects(&['b', 'x']));
+    }
+
+    #[test]
+    fn cycles() {
+        let dag = Dag::from_edges([(1, 2), (2, 3), (3, 4), (4, 2), (0, 1)]);
+
+        assert_eq!(dag.find_cycle(), Some(vec![2, 3, 4, 2]));
+        assert_eq!(dag.topological_sort().unwrap_err().to_string(), "cycle through 2 -> 3 -> 4 -> 2");
+        assert_eq!(dag.subgraph(&[4, 3, 1]).topological_sort(), Ok(vec![3, 4, 1]));
+        assert_eq!(dag.subgraph(&[3, 2, 1, 0]).topological_sort(), Ok(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn closure() {
+        let dag = Dag::from_edges([(1, 2), (2, 3), (4, 3)]);
+
+        assert_eq!(dag.descendants(1), HashSet::from([2, 3]));
+        assert!(dag.precedes(1, 3));
+        assert!(!dag.precedes(3, 1));
+        assert!(!dag.precedes(1, 4));
+        assert!(dag.descendants(5).is_empty());
+    }
+}
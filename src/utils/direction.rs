@@ -0,0 +1,197 @@
+use std::fmt;
+
+use super::coord::Coord;
+
+/// One of the four orthogonal compass directions, with north pointing up the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    N,
+    E,
+    S,
+    W,
+}
+impl Direction {
+    /// Clockwise from north.
+    pub const ALL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Direction::ALL.into_iter()
+    }
+
+    /// Turns 90 degrees anticlockwise.
+    pub fn left(self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    /// Turns 90 degrees clockwise.
+    pub fn right(self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn around(self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+
+    /// The unit vector one step in this direction.
+    pub fn delta(self) -> Coord {
+        match self {
+            Direction::N => Coord::new(0, -1),
+            Direction::E => Coord::new(1, 0),
+            Direction::S => Coord::new(0, 1),
+            Direction::W => Coord::new(-1, 0),
+        }
+    }
+
+    /// Position in `ALL`, for indexing per-direction state.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Reads an arrow `^>v<` only, for inputs where compass letters would be a mistake.
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::N),
+            '>' => Some(Direction::E),
+            'v' => Some(Direction::S),
+            '<' => Some(Direction::W),
+            _ => None,
+        }
+    }
+}
+
+/// Accepts arrows `^>v<` as well as compass letters `NESW`.
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'N' => Ok(Direction::N),
+            '>' | 'E' => Ok(Direction::E),
+            'v' | 'S' => Ok(Direction::S),
+            '<' | 'W' => Ok(Direction::W),
+            _ => Err(ParseDirectionError(c)),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            Direction::N => '^',
+            Direction::E => '>',
+            Direction::S => 'v',
+            Direction::W => '<',
+        };
+        write!(f, "{c}")
+    }
+}
+
+/// One of the eight compass directions, including diagonals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+impl Direction8 {
+    /// Clockwise from north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::N,
+        Direction8::NE,
+        Direction8::E,
+        Direction8::SE,
+        Direction8::S,
+        Direction8::SW,
+        Direction8::W,
+        Direction8::NW,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Direction8> {
+        Direction8::ALL.into_iter()
+    }
+
+    /// Turns 45 degrees anticlockwise.
+    pub fn left(self) -> Direction8 {
+        Direction8::ALL[(self.index() + 7) % 8]
+    }
+
+    /// Turns 45 degrees clockwise.
+    pub fn right(self) -> Direction8 {
+        Direction8::ALL[(self.index() + 1) % 8]
+    }
+
+    pub fn around(self) -> Direction8 {
+        Direction8::ALL[(self.index() + 4) % 8]
+    }
+
+    /// The step in this direction, so diagonals move one in each axis.
+    pub fn delta(self) -> Coord {
+        match self {
+            Direction8::N => Coord::new(0, -1),
+            Direction8::NE => Coord::new(1, -1),
+            Direction8::E => Coord::new(1, 0),
+            Direction8::SE => Coord::new(1, 1),
+            Direction8::S => Coord::new(0, 1),
+            Direction8::SW => Coord::new(-1, 1),
+            Direction8::W => Coord::new(-1, 0),
+            Direction8::NW => Coord::new(-1, -1),
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Direction8 {
+        Direction8::ALL[direction.index() * 2]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseDirectionError(pub char);
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unrecognised direction {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        assert_eq!(Direction::N.left(), Direction::W);
+        assert_eq!(Direction::W.right(), Direction::N);
+        assert_eq!(Direction::E.around(), Direction::W);
+        assert!(Direction::iter().all(|d| d.left().right() == d && d.around().around() == d));
+
+        assert_eq!(Direction8::N.left(), Direction8::NW);
+        assert_eq!(Direction8::NW.right(), Direction8::N);
+        assert_eq!(Direction8::SE.around(), Direction8::NW);
+    }
+
+    #[test]
+    fn deltas() {
+        assert!(Direction::iter().all(|d| d.delta() + d.around().delta() == Coord::ORIGIN));
+        assert!(Direction8::iter().all(|d| d.delta() + d.around().delta() == Coord::ORIGIN));
+        assert!(Direction::iter().all(|d| Direction8::from(d).delta() == d.delta()));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Direction::try_from('^'), Ok(Direction::N));
+        assert_eq!(Direction::try_from('W'), Ok(Direction::W));
+        assert_eq!(Direction::try_from('x'), Err(ParseDirectionError('x')));
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::S));
+        assert_eq!(Direction::from_arrow('S'), None);
+        assert_eq!(Direction::iter().map(|d| d.to_string()).collect::<String>(), "^>v<");
+    }
+}
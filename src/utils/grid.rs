@@ -0,0 +1,199 @@
+use std::fmt;
+
+use super::coord::Coord;
+
+/// A rectangular grid stored row by row, addressed by signed coordinates so that neighbours off
+/// the edge can be looked up without underflowing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> where T: Clone {
+        Grid {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Builds a grid from rows of cells, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+
+        if let Some(row) = rows.iter().position(|row| row.len() != width) {
+            panic!("row {row} of grid has {} cells, expected {width}", rows[row].len());
+        }
+
+        Grid {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.x >= 0 && coord.y >= 0 && (coord.x as usize) < self.width && (coord.y as usize) < self.height
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.index(coord).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.index(coord).map(|index| &mut self.cells[index])
+    }
+
+    /// Sets a cell, returning false if it is out of bounds.
+    pub fn set(&mut self, coord: Coord, value: T) -> bool {
+        match self.get_mut(coord) {
+            Some(cell) => {
+                *cell = value;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Every cell with its coordinate, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (self.coord(index), cell))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Coord, &mut T)> {
+        let width = self.width;
+
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, cell)| (Coord::new((index % width) as isize, (index / width) as isize), cell))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside a grid of width {}", self.width);
+
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    /// The coordinate of the first cell matching `predicate`, row by row.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Coord> {
+        self.cells.iter().position(predicate).map(|index| self.coord(index))
+    }
+
+    /// The coordinate of the first cell equal to `value`, such as a start marker.
+    pub fn find(&self, value: &T) -> Option<Coord> where T: PartialEq {
+        self.position(|cell| cell == value)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn index(&self, coord: Coord) -> Option<usize> {
+        match self.contains(coord) {
+            true => Some(coord.y as usize * self.width + coord.x as usize),
+            false => None,
+        }
+    }
+
+    fn coord(&self, index: usize) -> Coord {
+        Coord::new((index % self.width) as isize, (index / self.width) as isize)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse;
+
+    const INPUT: &str = "\
+        #S.\n\
+        .#E\n\
+    ";
+
+    #[test]
+    fn parse() {
+        let grid = parse::grid(INPUT, |c| Some(c == '#')).unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Coord::new(0, 0)), Some(&true));
+        assert_eq!(grid.get(Coord::new(2, 1)), Some(&false));
+        assert_eq!(grid.get(Coord::new(-1, 0)), None);
+        assert_eq!(grid.get(Coord::new(3, 0)), None);
+        assert_eq!(grid.get(Coord::new(0, 2)), None);
+    }
+
+    #[test]
+    fn find() {
+        let grid = parse::grid(INPUT, Some).unwrap();
+
+        assert_eq!(grid.find(&'S'), Some(Coord::new(1, 0)));
+        assert_eq!(grid.find(&'E'), Some(Coord::new(2, 1)));
+        assert_eq!(grid.find(&'@'), None);
+    }
+
+    #[test]
+    fn views() {
+        let grid = parse::grid(INPUT, Some).unwrap();
+
+        assert_eq!(grid.row(1), &['.', '#', 'E']);
+        assert_eq!(grid.column(2).collect::<String>(), ".E");
+        assert_eq!(grid.iter().nth(4), Some((Coord::new(1, 1), &'#')));
+        assert_eq!(grid.to_string(), INPUT);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ragged() {
+        Grid::from_rows(vec![vec!['.', '.'], vec!['.']]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn column_outside() {
+        let _ = Grid::new(3, 2, '.').column(3);
+    }
+}
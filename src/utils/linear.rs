@@ -0,0 +1,214 @@
+use std::{fmt, ops::{Add, Div, Mul, Sub}};
+
+use super::number_theory::gcd;
+
+/// An exact fraction, kept in lowest terms with a positive denominator. Arithmetic panics if it
+/// overflows `i128`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Rational {
+        assert!(denominator != 0, "rational with a zero denominator");
+
+        let divisor = gcd(numerator, denominator).expect("rational overflows i128");
+        let sign = denominator.signum();
+
+        Rational {
+            numerator: sign * numerator / divisor,
+            denominator: sign * denominator / divisor,
+        }
+    }
+
+    pub fn integer(n: i128) -> Rational {
+        Rational { numerator: n, denominator: 1 }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    /// The value if it is a whole number.
+    pub fn to_integer(self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+}
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Rational {
+        Rational::new(
+            checked(self.numerator.checked_mul(rhs.denominator).and_then(|a| {
+                rhs.numerator.checked_mul(self.denominator).and_then(|b| a.checked_add(b))
+            })),
+            checked(self.denominator.checked_mul(rhs.denominator)),
+        )
+    }
+}
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Rational {
+        self + Rational::integer(-1) * rhs
+    }
+}
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Rational {
+        Rational::new(
+            checked(self.numerator.checked_mul(rhs.numerator)),
+            checked(self.denominator.checked_mul(rhs.denominator)),
+        )
+    }
+}
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Rational) -> Rational {
+        Rational::new(
+            checked(self.numerator.checked_mul(rhs.denominator)),
+            checked(self.denominator.checked_mul(rhs.numerator)),
+        )
+    }
+}
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+/// The outcome of solving a system of linear equations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution {
+    /// Exactly one value for each unknown.
+    Unique(Vec<Rational>),
+    /// The equations contradict each other.
+    None,
+    /// Some unknowns can take any value.
+    Infinite,
+}
+
+/// Solves `coefficients · x = constants` exactly, with one row of coefficients per equation. Uses
+/// fraction-free Gaussian elimination, so intermediate values stay integers; panics if they
+/// overflow `i128`.
+pub fn solve(coefficients: &[Vec<i128>], constants: &[i128]) -> Solution {
+    assert_eq!(coefficients.len(), constants.len(), "one constant per equation");
+
+    let unknowns = coefficients.first().map_or(0, Vec::len);
+    let mut rows: Vec<Vec<i128>> = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, constant)| {
+            assert_eq!(row.len(), unknowns, "every equation has the same number of unknowns");
+            row.iter().copied().chain([*constant]).collect()
+        })
+        .collect();
+
+    let mut rank = 0;
+    let mut previous_pivot = 1;
+
+    for column in 0..unknowns {
+        let Some(pivot_row) = (rank..rows.len()).find(|row| rows[*row][column] != 0) else {
+            continue;
+        };
+        rows.swap(rank, pivot_row);
+
+        let (upper, lower) = rows.split_at_mut(rank + 1);
+        let pivot_row = &upper[rank];
+        let pivot = pivot_row[column];
+
+        for row in lower {
+            let factor = row[column];
+
+            // Each entry becomes a minor of the original matrix, so the division is exact.
+            for (entry, value) in row.iter_mut().enumerate().skip(column) {
+                let eliminated = checked(pivot.checked_mul(*value).and_then(|a| {
+                    factor.checked_mul(pivot_row[entry]).and_then(|b| a.checked_sub(b))
+                }));
+                *value = eliminated / previous_pivot;
+            }
+        }
+
+        previous_pivot = pivot;
+        rank += 1;
+    }
+
+    if rows[rank..].iter().any(|row| row[unknowns] != 0) {
+        return Solution::None;
+    }
+    if rank < unknowns {
+        return Solution::Infinite;
+    }
+
+    let mut values = vec![Rational::integer(0); unknowns];
+    for row in (0..unknowns).rev() {
+        let known = ((row + 1)..unknowns)
+            .map(|column| Rational::integer(rows[row][column]) * values[column])
+            .fold(Rational::integer(0), |sum, term| sum + term);
+
+        values[row] = (Rational::integer(rows[row][unknowns]) - known) / Rational::integer(rows[row][row]);
+    }
+
+    Solution::Unique(values)
+}
+
+fn checked(value: Option<i128>) -> i128 {
+    value.expect("linear arithmetic overflows i128")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractions() {
+        let half = Rational::new(2, 4);
+
+        assert_eq!(half, Rational::new(-1, -2));
+        assert_eq!((half + Rational::new(1, 3)).to_string(), "5/6");
+        assert_eq!((half - half).to_integer(), Some(0));
+        assert_eq!((half / Rational::new(-1, 4)).to_integer(), Some(-2));
+        assert_eq!((half * half).to_integer(), None);
+    }
+
+    #[test]
+    fn unique() {
+        let solution = solve(&[vec![94, 22], vec![34, 67]], &[8400, 5400]);
+
+        assert_eq!(solution, Solution::Unique(vec![Rational::integer(80), Rational::integer(40)]));
+
+        let solution = solve(&[vec![0, 2, 1], vec![1, 1, 1], vec![2, 0, 3]], &[5, 6, 11]);
+
+        assert_eq!(solution, Solution::Unique(vec![Rational::new(5, 2), Rational::new(3, 2), Rational::integer(2)]));
+    }
+
+    #[test]
+    fn degenerate() {
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 7]), Solution::None);
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 6]), Solution::Infinite);
+        assert_eq!(solve(&[vec![1], vec![2], vec![3]], &[2, 4, 6]), Solution::Unique(vec![Rational::integer(2)]));
+    }
+
+    #[test]
+    fn large() {
+        let prize = 10_000_000_000_000 + 8400;
+        let solution = solve(&[vec![94, 22], vec![34, 67]], &[prize, prize - 3000]);
+
+        match solution {
+            Solution::Unique(values) => {
+                assert_eq!(values[0] * Rational::integer(94) + values[1] * Rational::integer(22), Rational::integer(prize));
+            }
+            _ => panic!("expected a unique solution"),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, fmt, hash::Hash, sync::atomic::{AtomicBool, Ordering}};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Turns on printing each memo's statistics when it is dropped.
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// A cache for a recursive function, keyed by its arguments. The function is handed the memo
+/// back so that it can recurse through it.
+#[derive(Debug)]
+pub struct Memo<K: Hash + Eq, V> {
+    name: &'static str,
+    cache: HashMap<K, V>,
+    hits: usize,
+    misses: usize,
+}
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    /// The name labels the statistics printed in verbose mode.
+    pub fn new(name: &'static str) -> Memo<K, V> {
+        Memo {
+            name,
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+        let value = f(self);
+        self.cache.insert(key, value.clone());
+
+        value
+    }
+}
+impl<K: Hash + Eq, V> Memo<K, V> {
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            size: self.cache.len(),
+        }
+    }
+}
+impl<K: Hash + Eq, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        if VERBOSE.load(Ordering::Relaxed) {
+            eprintln!("memo {}: {}", self.name, self.stats());
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    pub size: usize,
+}
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} hits, {} misses, {} entries", self.hits, self.misses, self.size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        memo.get_or_insert_with(n, |memo| match n {
+            0 | 1 => n,
+            _ => fibonacci(n - 1, memo) + fibonacci(n - 2, memo),
+        })
+    }
+
+    #[test]
+    fn recursion() {
+        let mut memo = Memo::new("fibonacci");
+
+        assert_eq!(fibonacci(90, &mut memo), 2_880_067_194_370_816_120);
+        assert_eq!(memo.stats(), Stats { hits: 88, misses: 91, size: 91 });
+
+        fibonacci(90, &mut memo);
+
+        assert_eq!(memo.stats().hits, 89);
+        assert_eq!(memo.stats().to_string(), "89 hits, 91 misses, 91 entries");
+    }
+}
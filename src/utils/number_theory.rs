@@ -0,0 +1,170 @@
+use std::fmt::Debug;
+
+/// The signed integer types the helpers below work over.
+pub trait Integer: Copy + Ord + Debug {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    fn checked_rem_euclid(self, other: Self) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+
+            fn checked_add(self, other: $t) -> Option<$t> {
+                <$t>::checked_add(self, other)
+            }
+            fn checked_sub(self, other: $t) -> Option<$t> {
+                <$t>::checked_sub(self, other)
+            }
+            fn checked_mul(self, other: $t) -> Option<$t> {
+                <$t>::checked_mul(self, other)
+            }
+            fn checked_div(self, other: $t) -> Option<$t> {
+                <$t>::checked_div(self, other)
+            }
+            fn checked_rem_euclid(self, other: $t) -> Option<$t> {
+                <$t>::checked_rem_euclid(self, other)
+            }
+            fn checked_abs(self) -> Option<$t> {
+                <$t>::checked_abs(self)
+            }
+        }
+    )*};
+}
+integer!(i64, i128);
+
+/// The greatest common divisor, which is never negative. `gcd(0, 0)` is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a.checked_abs()?, b.checked_abs()?);
+
+    while b != T::ZERO {
+        (a, b) = (b, a.checked_rem_euclid(b)?);
+    }
+
+    Some(a)
+}
+
+/// The least common multiple, which is never negative, or `None` if it overflows.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+
+    a.checked_div(gcd(a, b)?)?.checked_mul(b)?.checked_abs()
+}
+
+/// Finds `(g, x, y)` such that `a * x + b * y == g`, where `g` is the gcd of `a` and `b`.
+pub fn extended_gcd<T: Integer>(a: T, b: T) -> Option<(T, T, T)> {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (T::ONE, T::ZERO);
+    let (mut y0, mut y1) = (T::ZERO, T::ONE);
+
+    while r1 != T::ZERO {
+        let q = r0.checked_div(r1)?;
+
+        (r0, r1) = (r1, r0.checked_sub(q.checked_mul(r1)?)?);
+        (x0, x1) = (x1, x0.checked_sub(q.checked_mul(x1)?)?);
+        (y0, y1) = (y1, y0.checked_sub(q.checked_mul(y1)?)?);
+    }
+
+    if r0 < T::ZERO {
+        let minus_one = T::ZERO.checked_sub(T::ONE)?;
+        return Some((r0.checked_mul(minus_one)?, x0.checked_mul(minus_one)?, y0.checked_mul(minus_one)?));
+    }
+
+    Some((r0, x0, y0))
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1`, or `None` if `a` and `modulus` share a factor.
+pub fn mod_inverse<T: Integer>(a: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd(a.checked_rem_euclid(modulus)?, modulus)?;
+
+    if g != T::ONE {
+        return None;
+    }
+
+    x.checked_rem_euclid(modulus)
+}
+
+/// Combines congruences `x ≡ residue (mod modulus)` into a single `(residue, modulus)`, where the
+/// modulus is the lcm of them all. The moduli needn't be coprime. Returns `None` if the
+/// congruences contradict each other or the arithmetic overflows.
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences.iter().try_fold((T::ZERO, T::ONE), |(r1, m1), &(r2, m2)| {
+        if m2 <= T::ZERO {
+            return None;
+        }
+
+        let (g, p, _) = extended_gcd(m1, m2)?;
+        let difference = r2.checked_sub(r1)?;
+
+        if difference.checked_rem_euclid(g)? != T::ZERO {
+            return None;
+        }
+
+        let step = m2.checked_div(g)?;
+        let modulus = m1.checked_mul(step)?;
+        // Both factors are reduced first so that only their product can overflow.
+        let k = difference
+            .checked_div(g)?
+            .checked_rem_euclid(step)?
+            .checked_mul(p.checked_rem_euclid(step)?)?
+            .checked_rem_euclid(step)?;
+        let residue = r1.checked_add(m1.checked_mul(k)?)?.checked_rem_euclid(modulus)?;
+
+        Some((residue, modulus))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divisors() {
+        assert_eq!(gcd(48i64, -18), Some(6));
+        assert_eq!(gcd(0i64, 0), Some(0));
+        assert_eq!(lcm(4i64, 6), Some(12));
+        assert_eq!(lcm(i64::MAX, i64::MAX - 1), None);
+        assert_eq!(gcd(i64::MIN, 0), None);
+
+        let (g, x, y) = extended_gcd(240i128, 46).unwrap();
+
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, g);
+    }
+
+    #[test]
+    fn inverses() {
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(-3i64, 11), Some(7));
+        assert_eq!(mod_inverse(6i64, 9), None);
+    }
+
+    #[test]
+    fn remainders() {
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1i64, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1i64, 4), (2, 6)]), None);
+        assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+
+        let large = crt(&[(1i128, 1_000_000_007), (2, 998_244_353)]).unwrap();
+
+        assert_eq!(large.1, 1_000_000_007 * 998_244_353);
+        assert_eq!(large.0 % 1_000_000_007, 1);
+        assert_eq!(large.0 % 998_244_353, 2);
+    }
+}
@@ -0,0 +1,426 @@
+use std::{any::type_name, error::Error, fmt, panic::panic_any, str::FromStr};
+
+use super::grid::Grid;
+
+/// Where and why some text failed to parse. Lines and columns count from one within the text
+/// that was handed to the parser. The day is filled in once a solution raises the error.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub day: Option<&'static str>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: impl Into<String>, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            day: None,
+            line,
+            column,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error pointing at `part`, which must be a slice of `text`.
+    pub fn at(text: &str, part: &str, expected: impl Into<String>) -> ParseError {
+        let (line, column) = locate(text, part);
+        ParseError::new(line, column, part, expected)
+    }
+
+    /// Moves an error found while parsing `part` so that it points into the enclosing `text`.
+    pub fn within(self, text: &str, part: &str) -> ParseError {
+        let (line, column) = locate(text, part);
+
+        ParseError {
+            column: if self.line == 1 { self.column + column - 1 } else { self.column },
+            line: self.line + line - 1,
+            ..self
+        }
+    }
+
+    /// Shows the offending line of the day's input with a caret under the problem.
+    pub fn render(&self, input: &str) -> String {
+        let day = self.day.unwrap_or("the");
+        let source = input.lines().nth(self.line.saturating_sub(1)).unwrap_or("");
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let indent = " ".repeat(self.column.saturating_sub(1));
+        let carets = "^".repeat(self.text.lines().next().map_or(0, |text| text.chars().count()).max(1));
+
+        format!(
+            "error: failed to parse {day} input\n\
+            {gutter}--> line {}, column {}\n\
+            {gutter} |\n\
+            {number} | {source}\n\
+            {gutter} | {indent}{carets} expected {}",
+            self.line, self.column, self.expected,
+        )
+    }
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "{day}, ")?;
+        }
+        write!(f, "line {}, column {}: expected {}, found {:?}", self.line, self.column, self.expected, self.text)
+    }
+}
+impl Error for ParseError {}
+
+/// Abandons solving with a parse error, which the runner reports against the input. `module` is
+/// the path of the solution raising it, whose last part names the day.
+pub fn raise_in<T>(module: &'static str, err: ParseError) -> T {
+    let day = module.rsplit("::").next().unwrap_or(module);
+    panic_any(ParseError { day: Some(day), ..err })
+}
+
+/// The handler for a solution's parse errors, which tags them with its day before raising them:
+///
+/// `let lab = get_lab(input).unwrap_or_else(raise!());`
+#[allow(unused_macros)]
+macro_rules! raise {
+    () => {
+        |err| $crate::utils::parse::raise_in(module_path!(), err)
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use raise;
+
+/// The line and column at which `part` starts within `text`, or the very start if it is not a
+/// slice of it.
+fn locate(text: &str, part: &str) -> (usize, usize) {
+    let start = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+
+    match text.get(..start) {
+        Some(before) => (
+            before.matches('\n').count() + 1,
+            before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+        ),
+        None => (1, 1),
+    }
+}
+
+/// Parses every line with `f`, placing any error at its line in the whole text.
+pub fn each_line<T>(text: &str, mut f: impl FnMut(&str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+    text
+        .lines()
+        .map(|line| f(line).map_err(|err| err.within(text, line)))
+        .collect()
+}
+
+/// Pulls every integer out of some text, ignoring whatever surrounds them. A `-` or `+` directly
+/// before the digits is taken as the sign, unless it follows another digit as in `3-5`.
+pub fn ints<T: FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    let mut ints = Vec::new();
+    let mut previous = None;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut start = 0;
+
+        while start < chars.len() {
+            let is_sign = matches!(chars[start], '-' | '+')
+                && chars.get(start + 1).is_some_and(char::is_ascii_digit)
+                && !previous.is_some_and(|c: char| c.is_ascii_digit());
+
+            if !is_sign && !chars[start].is_ascii_digit() {
+                previous = Some(chars[start]);
+                start += 1;
+                continue;
+            }
+
+            let end = (start + 1..chars.len())
+                .find(|index| !chars[*index].is_ascii_digit())
+                .unwrap_or(chars.len());
+            let token: String = chars[start..end].iter().collect();
+
+            let int = token.parse().map_err(|_| ParseError::new(
+                line_index + 1,
+                start + 1,
+                &token,
+                format!("an integer that fits in {}", type_name::<T>()),
+            ))?;
+
+            ints.push(int);
+            previous = chars.get(end - 1).copied();
+            start = end;
+        }
+
+        previous = None;
+    }
+
+    Ok(ints)
+}
+
+/// Like `ints`, but requires exactly `N` of them.
+pub fn ints_n<T: FromStr, const N: usize>(text: &str) -> Result<[T; N], ParseError> {
+    let ints = ints(text)?;
+    let found = ints.len();
+
+    ints.try_into().map_err(|_| ParseError::new(1, 1, text, format!("{N} integers, not {found}")))
+}
+
+/// Splits text into chunks separated by blank lines, skipping empty chunks.
+pub fn sections(text: &str) -> impl Iterator<Item = &str> {
+    text
+        .split("\n\n")
+        .map(|section| section.trim_matches('\n'))
+        .filter(|section| !section.is_empty())
+}
+
+/// Like `sections`, but requires exactly `N` of them.
+pub fn sections_n<const N: usize>(text: &str) -> Result<[&str; N], ParseError> {
+    let sections: Vec<&str> = sections(text).collect();
+    let found = sections.len();
+
+    sections.try_into().map_err(|_| ParseError::new(
+        text.lines().count(),
+        1,
+        text.lines().last().unwrap_or(""),
+        format!("{N} sections separated by blank lines, not {found}"),
+    ))
+}
+
+/// Separators for lists of numbers laid out in columns, for use with `numbers`.
+pub const WHITESPACE: &[char] = &[' ', '\t'];
+
+/// Parses a list of values split by any of the given separators, ignoring surrounding whitespace
+/// and empty entries, so `"1, 2,,3"` with `[',']` gives three numbers.
+pub fn numbers<T: FromStr>(text: &str, separators: &[char]) -> Result<Vec<T>, ParseError> {
+    let mut numbers = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let mut column = 1;
+
+        for item in line.split(separators) {
+            let trimmed = item.trim();
+
+            if !trimmed.is_empty() {
+                let offset = item.chars().take_while(|c| c.is_whitespace()).count();
+
+                numbers.push(trimmed.parse().map_err(|_| ParseError::new(
+                    line_index + 1,
+                    column + offset,
+                    trimmed,
+                    format!("a number of type {}", type_name::<T>()),
+                ))?);
+            }
+
+            column += item.chars().count() + 1;
+        }
+    }
+
+    Ok(numbers)
+}
+
+/// Like `numbers`, but requires exactly `N` of them.
+pub fn numbers_n<T: FromStr, const N: usize>(text: &str, separators: &[char]) -> Result<[T; N], ParseError> {
+    let numbers = numbers(text, separators)?;
+    let found = numbers.len();
+
+    numbers.try_into().map_err(|_| ParseError::new(1, 1, text, format!("{N} numbers, not {found}")))
+}
+
+/// Matches text against a pattern where each `{}` stands for a field, returning the text of each
+/// field. Everything else in the pattern, newlines included, must appear exactly.
+pub fn fields<'a, const N: usize>(text: &'a str, pattern: &str) -> Result<[&'a str; N], ParseError> {
+    let fields = scan_fields(text, pattern, N)?;
+    Ok(fields.try_into().unwrap())
+}
+
+/// The untyped half of `scan!`, which needs the fields as a list.
+pub fn scan_fields<'a>(text: &'a str, pattern: &str, count: usize) -> Result<Vec<&'a str>, ParseError> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+
+    if literals.len() != count + 1 {
+        panic!("pattern {pattern:?} has {} fields, expected {count}", literals.len() - 1);
+    }
+    if literals[1..count].iter().any(|literal| literal.is_empty()) {
+        panic!("pattern {pattern:?} has adjacent fields, which would be ambiguous");
+    }
+
+    let mismatch = |rest: &'a str, literal: &str| ParseError::at(text, rest, format!("{literal:?}"));
+
+    let mut rest = text.strip_prefix(literals[0]).ok_or_else(|| mismatch(text, literals[0]))?;
+    let mut fields = Vec::new();
+
+    for (index, literal) in literals.iter().enumerate().skip(1) {
+        let end = if index == count {
+            rest.strip_suffix(literal).map(str::len)
+        } else {
+            rest.find(literal)
+        };
+        let end = end.ok_or_else(|| mismatch(rest, literal))?;
+
+        fields.push(&rest[..end]);
+        rest = &rest[end + literal.len()..];
+    }
+
+    Ok(fields)
+}
+
+/// Parses one field matched by `scan!`.
+pub fn scan_field<T: FromStr>(text: &str, field: &str) -> Result<T, ParseError> {
+    field.parse().map_err(|_| ParseError::at(text, field, format!("a value of type {}", type_name::<T>())))
+}
+
+/// Parses text against a pattern into a tuple, one type per `{}` in the pattern:
+///
+/// `let (px, py, vx, vy) = scan!(line, "p={},{} v={},{}" => isize, isize, isize, isize)?;`
+#[allow(unused_macros)]
+macro_rules! scan {
+    ($text:expr, $pattern:literal => $($ty:ty),+ $(,)?) => {{
+        let text: &str = $text;
+        let count = [$(stringify!($ty)),+].len();
+
+        $crate::utils::parse::scan_fields(text, $pattern, count).and_then(|fields| {
+            let mut fields = fields.into_iter();
+            Ok(($($crate::utils::parse::scan_field::<$ty>(text, fields.next().unwrap())?,)+))
+        })
+    }};
+}
+#[allow(unused_imports)]
+pub(crate) use scan;
+
+/// Parses a character map, one row per line, where `f` returns `None` for characters it does not
+/// recognise.
+pub fn grid<T>(text: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+    let mut rows = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let mut row = Vec::new();
+
+        for (column_index, c) in line.chars().enumerate() {
+            let cell = f(c).ok_or_else(|| ParseError::new(
+                line_index + 1,
+                column_index + 1,
+                c,
+                "a recognised grid cell",
+            ))?;
+            row.push(cell);
+        }
+
+        if let Some(first) = rows.first().map(Vec::len).filter(|width| *width != row.len()) {
+            return Err(ParseError::new(
+                line_index + 1,
+                row.len().min(first) + 1,
+                line,
+                format!("a row of {first} cells"),
+            ));
+        }
+
+        rows.push(row);
+    }
+
+    Ok(Grid::from_rows(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers() {
+        assert_eq!(ints::<i64>("p=0,4 v=3,-3"), Ok(vec![0, 4, 3, -3]));
+        assert_eq!(ints::<usize>("Button A: X+94, Y+34"), Ok(vec![94, 34]));
+        assert_eq!(ints::<i32>("range 3-5\n-2 and --7"), Ok(vec![3, 5, -2, -7]));
+        assert_eq!(ints_n::<usize, 2>("Prize: X=8400, Y=5400"), Ok([8400, 5400]));
+
+        let err = ints::<usize>("a: 1\nb: -2").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "-2"));
+        assert!(ints_n::<usize, 3>("1 2").is_err());
+    }
+
+    #[test]
+    fn blank_line_sections() {
+        let input = "a\nb\n\nc\n\n\nd\n";
+
+        assert_eq!(sections(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+        assert_eq!(sections_n::<2>("a\n\nb\n"), Ok(["a", "b"]));
+        assert_eq!(sections_n::<2>("a\nb\n").unwrap_err().expected, "2 sections separated by blank lines, not 1");
+    }
+
+    #[test]
+    fn separated_numbers() {
+        assert_eq!(numbers::<usize>("75,47, 61,,53", &[',']), Ok(vec![75, 47, 61, 53]));
+        assert_eq!(numbers::<usize>("47|53\n97|13", &['|']), Ok(vec![47, 53, 97, 13]));
+
+        let err = numbers::<usize>("1, x", &[',']).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 4, "x"));
+
+        assert_eq!(numbers_n::<usize, 2>("3\t 4", WHITESPACE), Ok([3, 4]));
+        assert_eq!(numbers_n::<usize, 2>("47|53", &['|']), Ok([47, 53]));
+        assert_eq!(numbers_n::<usize, 2>("47,53", &['|']).unwrap_err().column, 1);
+        assert_eq!(numbers_n::<usize, 2>("3 x4", WHITESPACE).unwrap_err().text, "x4");
+        assert_eq!(numbers_n::<usize, 2>("3", WHITESPACE).unwrap_err().expected, "2 numbers, not 1");
+    }
+
+    #[test]
+    fn locations() {
+        let input = "1 2\n3 x\n";
+        let line = input.lines().nth(1).unwrap();
+
+        let err = ParseError::at(input, &line[2..], "a number");
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = each_line(input, |line| numbers::<usize>(line, &[' '])).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x"));
+
+        let expected = "\
+            error: failed to parse day_01 input\n \
+            --> line 2, column 3\n  \
+            |\n\
+            2 | 3 x\n  \
+            |   ^ expected a number of type usize\
+        ";
+        assert_eq!(err.render(input), expected.replace("day_01", "the"));
+
+        let raised = std::panic::catch_unwind(|| raise_in::<()>("aoc::solutions::day_01", err)).unwrap_err();
+        let err = raised.downcast_ref::<ParseError>().unwrap();
+
+        assert_eq!(err.day, Some("day_01"));
+        assert_eq!(err.to_string(), "day_01, line 2, column 3: expected a number of type usize, found \"x\"");
+        assert_eq!(err.render(input), expected);
+    }
+
+    #[test]
+    fn char_grid() {
+        let walls = grid("#.\n.#\n", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        });
+        assert_eq!(walls.map(|grid| grid.values().filter(|wall| **wall).count()), Ok(2));
+
+        let err = grid("..\n.x\n", |c| (c == '.').then_some(())).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+
+        let err = grid("..\n.\n", Some).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 2, "a row of 2 cells"));
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!(scan!("p=0,4 v=3,-3", "p={},{} v={},{}" => isize, isize, isize, isize), Ok((0, 4, 3, -3)));
+        assert_eq!(scan!("Button A: X+94, Y+34", "Button A: X+{}, Y+{}" => u64, u64), Ok((94, 34)));
+        assert_eq!(scan!("Prize: X=8400, Y=5400\n", "Prize: X={}, Y={}\n" => u64, String), Ok((8400, String::from("5400"))));
+        assert_eq!(fields("Program: 0,1,5", "Program: {}"), Ok(["0,1,5"]));
+
+        let err = scan!("Button B: X+22, Y+67", "Button A: X+{}, Y+{}" => u64, u64).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (1, "\"Button A: X+\""));
+
+        let err = scan!("p=0,4 v=3;-3", "p={},{} v={},{}" => isize, isize, isize, isize).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (9, "\",\""));
+
+        let err = scan!("a: 1\nb: x", "a: {}\nb: {}" => u8, u8).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "x"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn pattern_field_count() {
+        let _ = scan!("1,2", "{},{}" => u8);
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::{coord::Coord, grid::Grid};
+
+/// Which cells count as touching.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Orthogonal neighbours only.
+    Four,
+    /// Diagonal neighbours too.
+    Eight,
+}
+impl Connectivity {
+    pub fn neighbours(self, coord: Coord) -> Vec<Coord> {
+        match self {
+            Connectivity::Four => coord.neighbours().collect(),
+            Connectivity::Eight => coord.all_neighbours().collect(),
+        }
+    }
+
+    /// The connectivity to use for the cells outside a region, so that a diagonal gap can't both
+    /// join a region and let its outside through.
+    fn dual(self) -> Connectivity {
+        match self {
+            Connectivity::Four => Connectivity::Eight,
+            Connectivity::Eight => Connectivity::Four,
+        }
+    }
+}
+
+/// A connected set of cells.
+#[derive(Clone, Debug)]
+pub struct Region {
+    cells: Vec<Coord>,
+    members: HashSet<Coord>,
+    connectivity: Connectivity,
+}
+impl Region {
+    fn new(mut cells: Vec<Coord>, connectivity: Connectivity) -> Region {
+        cells.sort();
+        let members = cells.iter().copied().collect();
+
+        Region { cells, members, connectivity }
+    }
+
+    /// The cells in reading order.
+    pub fn cells(&self) -> &[Coord] {
+        &self.cells
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        self.members.contains(&coord)
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The number of cell edges between the region and anything outside it, holes included.
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .flat_map(|cell| cell.neighbours())
+            .filter(|neighbour| !self.contains(*neighbour))
+            .count()
+    }
+
+    /// The top-left and bottom-right cells of the smallest box around the region.
+    pub fn bounds(&self) -> (Coord, Coord) {
+        let min = Coord::new(
+            self.cells.iter().map(|cell| cell.x).min().unwrap_or(0),
+            self.cells.iter().map(|cell| cell.y).min().unwrap_or(0),
+        );
+        let max = Coord::new(
+            self.cells.iter().map(|cell| cell.x).max().unwrap_or(0),
+            self.cells.iter().map(|cell| cell.y).max().unwrap_or(0),
+        );
+
+        (min, max)
+    }
+
+    /// The number of corners on the region's outline, holes included. Every straight side ends in
+    /// a corner, so this is also the number of sides.
+    pub fn corners(&self) -> usize {
+        const DIAGONALS: [(Coord, Coord); 4] = [
+            (Coord::new(0, -1), Coord::new(1, 0)),
+            (Coord::new(1, 0), Coord::new(0, 1)),
+            (Coord::new(0, 1), Coord::new(-1, 0)),
+            (Coord::new(-1, 0), Coord::new(0, -1)),
+        ];
+
+        self.cells
+            .iter()
+            .map(|cell| DIAGONALS
+                .iter()
+                .filter(|(a, b)| {
+                    let has_a = self.contains(*cell + *a);
+                    let has_b = self.contains(*cell + *b);
+                    let has_diagonal = self.contains(*cell + *a + *b);
+
+                    (!has_a && !has_b) || (has_a && has_b && !has_diagonal)
+                })
+                .count()
+            )
+            .sum()
+    }
+
+    pub fn sides(&self) -> usize {
+        self.corners()
+    }
+
+    /// The number of separate pockets of outside cells the region completely encloses.
+    pub fn holes(&self) -> usize {
+        let (min, max) = self.bounds();
+        let (min, max) = (min - Coord::new(1, 1), max + Coord::new(1, 1));
+
+        let is_inside = |coord: Coord| coord.x >= min.x && coord.y >= min.y && coord.x <= max.x && coord.y <= max.y;
+        let dual = self.connectivity.dual();
+
+        let mut seen = HashSet::new();
+        let mut holes: usize = 0;
+
+        // The corner of the padded box is always outside, so the first fill is not a hole.
+        let outside = (min.y..=max.y)
+            .flat_map(|y| (min.x..=max.x).map(move |x| Coord::new(x, y)))
+            .filter(|coord| !self.contains(*coord));
+
+        for start in outside {
+            if seen.insert(start) {
+                let mut queue = VecDeque::from([start]);
+
+                while let Some(coord) = queue.pop_front() {
+                    for neighbour in dual.neighbours(coord) {
+                        if is_inside(neighbour) && !self.contains(neighbour) && seen.insert(neighbour) {
+                            queue.push_back(neighbour);
+                        }
+                    }
+                }
+
+                holes += 1;
+            }
+        }
+
+        holes.saturating_sub(1)
+    }
+}
+
+/// The region around `start` of cells for which `include` holds.
+pub fn flood_fill<T>(
+    grid: &Grid<T>,
+    start: Coord,
+    connectivity: Connectivity,
+    mut include: impl FnMut(Coord, &T) -> bool,
+) -> Region {
+    let mut cells = Vec::new();
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::new();
+
+    if grid.get(start).is_some_and(|value| include(start, value)) {
+        queue.push_back(start);
+    }
+
+    while let Some(coord) = queue.pop_front() {
+        cells.push(coord);
+
+        for neighbour in connectivity.neighbours(coord) {
+            if let Some(value) = grid.get(neighbour) {
+                if seen.insert(neighbour) && include(neighbour, value) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+    }
+
+    Region::new(cells, connectivity)
+}
+
+/// Splits the whole grid into regions of equal values, in reading order of their first cell.
+pub fn components<T: PartialEq>(grid: &Grid<T>, connectivity: Connectivity) -> Vec<Region> {
+    label(grid, connectivity, |_value| true, |a, b| a == b)
+}
+
+/// Groups the cells for which `include` holds into regions, in reading order of their first cell.
+pub fn components_where<T>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    include: impl Fn(&T) -> bool,
+) -> Vec<Region> {
+    label(grid, connectivity, include, |_a, _b| true)
+}
+
+fn label<T>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    include: impl Fn(&T) -> bool,
+    same: impl Fn(&T, &T) -> bool,
+) -> Vec<Region> {
+    let mut labelled = grid.map(|_value| false);
+    let mut regions = Vec::new();
+
+    for (start, value) in grid.iter() {
+        if labelled.get(start) == Some(&true) || !include(value) {
+            continue;
+        }
+
+        let region = flood_fill(grid, start, connectivity, |_coord, other| include(other) && same(value, other));
+
+        for cell in region.cells() {
+            labelled.set(*cell, true);
+        }
+        regions.push(region);
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse;
+
+    const INPUT: &str = "\
+        AAAAAA\n\
+        AAABBA\n\
+        AAABBA\n\
+        ABBAAA\n\
+        ABBAAA\n\
+        AAAAAA\n\
+    ";
+
+    #[test]
+    fn equal_values() {
+        let grid = parse::grid(INPUT, Some).unwrap();
+        let regions = components(&grid, Connectivity::Four);
+
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions.iter().map(Region::area).collect::<Vec<_>>(), vec![28, 4, 4]);
+        assert_eq!(regions.iter().map(Region::perimeter).collect::<Vec<_>>(), vec![40, 8, 8]);
+        assert_eq!(regions.iter().map(Region::sides).collect::<Vec<_>>(), vec![12, 4, 4]);
+        // The two B blocks touch at a corner, so they form a single hole.
+        assert_eq!(regions[0].holes(), 1);
+        assert_eq!(regions[1].holes(), 0);
+        assert_eq!(regions[1].bounds(), (Coord::new(3, 1), Coord::new(4, 2)));
+    }
+
+    #[test]
+    fn diagonal() {
+        let grid = parse::grid("\
+            #..\n\
+            .#.\n\
+            ..#\n\
+        ", Some).unwrap();
+
+        let four = components_where(&grid, Connectivity::Four, |cell| *cell == '#');
+        let eight = components_where(&grid, Connectivity::Eight, |cell| *cell == '#');
+
+        assert_eq!(four.len(), 3);
+        assert_eq!(eight.len(), 1);
+        assert_eq!(eight[0].cells(), [Coord::new(0, 0), Coord::new(1, 1), Coord::new(2, 2)]);
+    }
+
+    #[test]
+    fn fill() {
+        let grid = parse::grid("\
+            ###\n\
+            #.#\n\
+            ###\n\
+        ", Some).unwrap();
+
+        let ring = flood_fill(&grid, Coord::ORIGIN, Connectivity::Four, |_coord, cell| *cell == '#');
+
+        assert_eq!(ring.area(), 8);
+        assert_eq!(ring.holes(), 1);
+        assert_eq!(ring.sides(), 8);
+        assert!(!ring.contains(Coord::new(1, 1)));
+        assert_eq!(flood_fill(&grid, Coord::new(1, 1), Connectivity::Four, |_coord, cell| *cell == '#').area(), 0);
+    }
+}
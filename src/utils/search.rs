@@ -0,0 +1,653 @@
+use std::{cmp::Ordering, collections::{BinaryHeap, HashMap, VecDeque}, hash::Hash, rc::Rc};
+
+/// Somewhere a search keeps one value per node.
+pub trait NodeMap<N, V> {
+    fn get(&self, node: &N) -> Option<&V>;
+    fn get_mut(&mut self, node: &N) -> Option<&mut V>;
+    fn insert(&mut self, node: N, value: V);
+    fn len(&self) -> usize;
+
+    fn contains(&self, node: &N) -> bool {
+        self.get(node).is_some()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// How a search tells nodes apart, which decides the kind of map it stores them in. Each
+/// algorithm below is written once over this.
+pub trait Keys<N> {
+    type Map<V>: NodeMap<N, V>;
+
+    fn map<V>(&self) -> Self::Map<V>;
+}
+
+/// Nodes are hashed, which suits graphs with no natural numbering.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hashed;
+impl<N: Copy + Eq + Hash> Keys<N> for Hashed {
+    type Map<V> = HashMap<N, V>;
+
+    fn map<V>(&self) -> HashMap<N, V> {
+        HashMap::new()
+    }
+}
+impl<N: Copy + Eq + Hash, V> NodeMap<N, V> for HashMap<N, V> {
+    fn get(&self, node: &N) -> Option<&V> {
+        HashMap::get(self, node)
+    }
+
+    fn get_mut(&mut self, node: &N) -> Option<&mut V> {
+        HashMap::get_mut(self, node)
+    }
+
+    fn insert(&mut self, node: N, value: V) {
+        HashMap::insert(self, node, value);
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// Nodes are numbered below a fixed capacity, as by `BitGrid::index`, so their values live in a
+/// plain vector.
+pub struct Indexed<F> {
+    capacity: usize,
+    index: Rc<F>,
+}
+impl<F> Indexed<F> {
+    pub fn new(capacity: usize, index: F) -> Indexed<F> {
+        Indexed { capacity, index: Rc::new(index) }
+    }
+}
+impl<N, F: Fn(&N) -> usize> Keys<N> for Indexed<F> {
+    type Map<V> = IndexedMap<V, F>;
+
+    fn map<V>(&self) -> IndexedMap<V, F> {
+        IndexedMap {
+            index: Rc::clone(&self.index),
+            values: (0..self.capacity).map(|_| None).collect(),
+            len: 0,
+        }
+    }
+}
+
+pub struct IndexedMap<V, F> {
+    index: Rc<F>,
+    values: Vec<Option<V>>,
+    len: usize,
+}
+impl<N, V, F: Fn(&N) -> usize> NodeMap<N, V> for IndexedMap<V, F> {
+    fn get(&self, node: &N) -> Option<&V> {
+        self.values[(self.index)(node)].as_ref()
+    }
+
+    fn get_mut(&mut self, node: &N) -> Option<&mut V> {
+        self.values[(self.index)(node)].as_mut()
+    }
+
+    fn insert(&mut self, node: N, value: V) {
+        let slot = &mut self.values[(self.index)(&node)];
+        if slot.is_none() {
+            self.len += 1;
+        }
+
+        *slot = Some(value);
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// The outcome of a search from a single start node.
+///
+/// Distances are only recorded for nodes whose shortest distance is known, so stopping early at a
+/// goal leaves the rest of the graph out. Pass a goal test that never matches to get the full
+/// distance map.
+pub struct Search<N, K: Keys<N> = Hashed> {
+    distances: K::Map<usize>,
+    previous: K::Map<N>,
+    goal: Option<N>,
+}
+impl<N: Copy, K: Keys<N>> Search<N, K> {
+    fn new(keys: &K) -> Search<N, K> {
+        Search {
+            distances: keys.map(),
+            previous: keys.map(),
+            goal: None,
+        }
+    }
+
+    /// The first node found matching the goal test, if any was reached.
+    pub fn goal(&self) -> Option<N> {
+        self.goal
+    }
+
+    pub fn distance(&self) -> Option<usize> {
+        self.distance_to(self.goal?)
+    }
+
+    /// The nodes from the start to the goal, inclusive.
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal?)
+    }
+
+    pub fn distance_to(&self, node: N) -> Option<usize> {
+        self.distances.get(&node).copied()
+    }
+
+    pub fn path_to(&self, node: N) -> Option<Vec<N>> {
+        if !self.distances.contains(&node) {
+            return None;
+        }
+
+        let mut path = vec![node];
+        let mut current = node;
+
+        while let Some(previous) = self.previous.get(&current) {
+            path.push(*previous);
+            current = *previous;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn distances(&self) -> &K::Map<usize> {
+        &self.distances
+    }
+}
+
+/// Breadth-first search, for graphs where every step costs one.
+pub fn bfs<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    bfs_with(Hashed, start, neighbours, is_goal)
+}
+
+/// Like `bfs`, over nodes that `index` numbers below `capacity`, such as the cells of a grid.
+pub fn bfs_dense<N, I, F>(
+    start: N,
+    capacity: usize,
+    index: F,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, Indexed<F>>
+where
+    N: Copy,
+    I: IntoIterator<Item = N>,
+    F: Fn(&N) -> usize,
+{
+    bfs_with(Indexed::new(capacity, index), start, neighbours, is_goal)
+}
+
+fn bfs_with<N, I, K>(
+    keys: K,
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, K>
+where
+    N: Copy,
+    I: IntoIterator<Item = N>,
+    K: Keys<N>,
+{
+    let mut search = Search::new(&keys);
+    let mut queue = VecDeque::from([start]);
+    search.distances.insert(start, 0);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        let distance = search.distance_to(node).expect("queued nodes have a distance");
+
+        for next in neighbours(&node) {
+            if !search.distances.contains(&next) {
+                search.distances.insert(next, distance + 1);
+                search.previous.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Dijkstra's algorithm, where `neighbours` yields each reachable node with the cost of the step.
+pub fn dijkstra<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+/// A* search. The heuristic must never overestimate the remaining cost, and must not drop by more
+/// than the cost of any step, or the distances found may not be the shortest.
+pub fn astar<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> usize,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar_with(Hashed, start, neighbours, heuristic, is_goal)
+}
+
+fn astar_with<N, I, K>(
+    keys: K,
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, K>
+where
+    N: Copy,
+    I: IntoIterator<Item = (N, usize)>,
+    K: Keys<N>,
+{
+    let mut search = Search::new(&keys);
+    let mut best = keys.map();
+    let mut queue = BinaryHeap::from([Queued { priority: heuristic(&start), cost: 0, node: start }]);
+    best.insert(start, 0);
+
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if search.distances.contains(&node) {
+            continue;
+        }
+
+        search.distances.insert(node, cost);
+
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+
+            if best.get(&next).is_none_or(|best| next_cost < *best) {
+                best.insert(next, next_cost);
+                search.previous.insert(next, node);
+                queue.push(Queued { priority: next_cost + heuristic(&next), cost: next_cost, node: next });
+            }
+        }
+    }
+
+    search
+}
+
+/// Every shortest path from a single start node to the nearest goals, kept as a graph of
+/// equal-cost predecessors rather than a list of paths.
+pub struct AllPaths<N, K: Keys<N> = Hashed> {
+    keys: K,
+    distances: K::Map<usize>,
+    predecessors: K::Map<Vec<N>>,
+    goals: Vec<N>,
+}
+impl<N: Copy, K: Keys<N>> AllPaths<N, K> {
+    /// Every goal node reached at the shortest distance.
+    pub fn goals(&self) -> &[N] {
+        &self.goals
+    }
+
+    pub fn distance(&self) -> Option<usize> {
+        self.distance_to(*self.goals.first()?)
+    }
+
+    pub fn distance_to(&self, node: N) -> Option<usize> {
+        self.distances.get(&node).copied()
+    }
+
+    pub fn predecessors(&self, node: N) -> &[N] {
+        self.predecessors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every node lying on at least one shortest path, including the start and goals, each listed
+    /// once.
+    pub fn nodes(&self) -> Vec<N> {
+        let mut seen: K::Map<()> = self.keys.map();
+        let mut stack = self.goals.clone();
+        let mut nodes = Vec::new();
+
+        for goal in &self.goals {
+            seen.insert(*goal, ());
+        }
+
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+
+            for previous in self.predecessors(node) {
+                if !seen.contains(previous) {
+                    seen.insert(*previous, ());
+                    stack.push(*previous);
+                }
+            }
+        }
+
+        nodes
+    }
+
+    /// The number of distinct shortest paths, counted without listing them.
+    pub fn count(&self) -> u128 {
+        let mut nodes = self.nodes();
+        nodes.sort_by_key(|node| self.distance_to(*node));
+
+        let mut counts: K::Map<u128> = self.keys.map();
+
+        for node in nodes {
+            let count = match self.predecessors(node) {
+                [] => 1,
+                previous => previous.iter().map(|previous| counts.get(previous).unwrap()).sum(),
+            };
+            counts.insert(node, count);
+        }
+
+        self.goals.iter().map(|goal| counts.get(goal).unwrap()).sum()
+    }
+
+    /// Lists every shortest path from the start. There can be exponentially many, so prefer
+    /// `nodes` or `count` on large graphs.
+    pub fn paths(&self) -> Vec<Vec<N>> {
+        let mut paths = Vec::new();
+        let mut stack: Vec<Vec<N>> = self.goals.iter().map(|goal| vec![*goal]).collect();
+
+        while let Some(path) = stack.pop() {
+            let node = *path.last().unwrap();
+
+            match self.predecessors(node) {
+                [] => paths.push(path.into_iter().rev().collect()),
+                previous => for previous in previous {
+                    let mut path = path.clone();
+                    path.push(*previous);
+                    stack.push(path);
+                },
+            }
+        }
+
+        paths
+    }
+}
+
+/// Dijkstra's algorithm, keeping every equal-cost predecessor so that all shortest paths to the
+/// goal can be recovered. Step costs must be positive.
+pub fn dijkstra_all<N, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> AllPaths<N>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    dijkstra_all_with(Hashed, start, neighbours, is_goal)
+}
+
+/// Like `dijkstra_all`, over nodes that `index` numbers below `capacity`, such as the states in a
+/// `StateSet`.
+pub fn dijkstra_all_dense<N, I, F>(
+    start: N,
+    capacity: usize,
+    index: F,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> AllPaths<N, Indexed<F>>
+where
+    N: Copy,
+    I: IntoIterator<Item = (N, usize)>,
+    F: Fn(&N) -> usize,
+{
+    dijkstra_all_with(Indexed::new(capacity, index), start, neighbours, is_goal)
+}
+
+fn dijkstra_all_with<N, I, K>(
+    keys: K,
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> AllPaths<N, K>
+where
+    N: Copy,
+    I: IntoIterator<Item = (N, usize)>,
+    K: Keys<N>,
+{
+    let mut best = keys.map();
+    let mut all = AllPaths {
+        distances: keys.map(),
+        predecessors: keys.map(),
+        goals: Vec::new(),
+        keys,
+    };
+    let mut queue = BinaryHeap::from([Queued { priority: 0, cost: 0, node: start }]);
+    best.insert(start, 0);
+
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if all.distance().is_some_and(|distance| cost > distance) {
+            break;
+        }
+
+        if all.distances.contains(&node) {
+            continue;
+        }
+
+        all.distances.insert(node, cost);
+
+        if is_goal(&node) {
+            all.goals.push(node);
+            continue;
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+
+            match best.get(&next) {
+                Some(best) if next_cost > *best => (),
+                Some(best) if next_cost == *best => match all.predecessors.get_mut(&next) {
+                    Some(predecessors) => predecessors.push(node),
+                    None => all.predecessors.insert(next, vec![node]),
+                },
+                _ => {
+                    best.insert(next, next_cost);
+                    all.predecessors.insert(next, vec![node]);
+                    queue.push(Queued { priority: next_cost, cost: next_cost, node: next });
+                },
+            }
+        }
+    }
+
+    all
+}
+
+/// A queued node, ordered so the max-heap pops the lowest priority first, breaking ties in favour
+/// of nodes further along.
+struct Queued<N> {
+    priority: usize,
+    cost: usize,
+    node: N,
+}
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority).then(self.cost.cmp(&other.cost))
+    }
+}
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl<N> Eq for Queued<N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{bit_grid::BitGrid, coord::Coord, grid::Grid, parse};
+
+    const INPUT: &str = "\
+        ..#....\n\
+        ..#.##.\n\
+        ....#..\n\
+        ###.#.#\n\
+        ......E\n\
+    ";
+
+    fn get_open(grid: &Grid<char>, coord: &Coord) -> Vec<Coord> {
+        coord.neighbours().filter(|n| grid.get(*n).is_some_and(|c| *c != '#')).collect()
+    }
+
+    #[test]
+    fn breadth_first() {
+        let grid = parse::grid(INPUT, Some).unwrap();
+        let end = grid.find(&'E').unwrap();
+
+        let search = bfs(Coord::ORIGIN, |coord| get_open(&grid, coord), |coord| *coord == end);
+        let path = search.path().unwrap();
+
+        assert_eq!(search.distance(), Some(10));
+        assert_eq!(path.len(), 11);
+        assert_eq!(path.first(), Some(&Coord::ORIGIN));
+        assert_eq!(path.last(), Some(&end));
+        assert!(path.windows(2).all(|step| step[0].is_adjacent(step[1])));
+    }
+
+    #[test]
+    fn weighted() {
+        // Stepping onto a lowercase cell costs 10, so the detour is cheaper.
+        let grid = parse::grid("\
+            .x.\n\
+            ...\n\
+        ", Some).unwrap();
+        let end = Coord::new(2, 0);
+        let neighbours = |coord: &Coord| coord
+            .neighbours()
+            .filter_map(|n| grid.get(n).map(|c| (n, if *c == 'x' { 10 } else { 1 })))
+            .collect::<Vec<_>>();
+
+        let plain = dijkstra(Coord::ORIGIN, neighbours, |coord| *coord == end);
+        let guided = astar(Coord::ORIGIN, neighbours, |coord| coord.manhattan(end), |coord| *coord == end);
+
+        assert_eq!(plain.distance(), Some(4));
+        assert_eq!(guided.distance(), Some(4));
+        assert_eq!(guided.path().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn distance_map() {
+        let grid = parse::grid(INPUT, Some).unwrap();
+
+        let search = bfs(Coord::ORIGIN, |coord| get_open(&grid, coord), |_| false);
+
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.distances().len(), grid.values().filter(|c| **c != '#').count());
+        assert_eq!(search.distance_to(Coord::new(6, 0)), Some(10));
+        assert_eq!(search.path_to(Coord::new(2, 0)), None);
+    }
+
+    #[test]
+    fn all_paths() {
+        // Two routes around the wall, and the goal can be reached heading either way.
+        let grid = parse::grid("\
+            ...\n\
+            .#.\n\
+            ...\n\
+        ", Some).unwrap();
+        let end = Coord::new(2, 2);
+
+        let all = dijkstra_all(
+            Coord::ORIGIN,
+            |coord| get_open(&grid, coord).into_iter().map(|n| (n, 1)).collect::<Vec<_>>(),
+            |coord| *coord == end,
+        );
+
+        assert_eq!(all.distance(), Some(4));
+        assert_eq!(all.goals(), [end]);
+        assert_eq!(all.count(), 2);
+        assert_eq!(all.nodes().len(), 8);
+        assert_eq!(all.paths().len(), 2);
+        assert!(all.paths().iter().all(|path| path.len() == 5 && path[0] == Coord::ORIGIN));
+    }
+
+    #[test]
+    fn count_without_listing() {
+        // A 20 by 20 open grid has 38 choose 19 monotonic paths corner to corner.
+        let grid = Grid::new(20, 20, '.');
+        let end = Coord::new(19, 19);
+
+        let all = dijkstra_all(
+            Coord::ORIGIN,
+            |coord| get_open(&grid, coord).into_iter().map(|n| (n, 1)).collect::<Vec<_>>(),
+            |coord| *coord == end,
+        );
+
+        assert_eq!(all.count(), 35_345_263_800);
+        assert_eq!(all.nodes().len(), 400);
+    }
+
+    #[test]
+    fn dense() {
+        let grid = parse::grid(INPUT, Some).unwrap();
+        let end = grid.find(&'E').unwrap();
+        let cells = BitGrid::like(&grid);
+        let index = |coord: &Coord| cells.index(*coord).unwrap();
+
+        let search = bfs_dense(Coord::ORIGIN, cells.capacity(), index, |coord| get_open(&grid, coord), |coord| *coord == end);
+        let expected = bfs(Coord::ORIGIN, |coord| get_open(&grid, coord), |coord| *coord == end);
+
+        assert_eq!(search.distance(), Some(10));
+        assert_eq!(search.path(), expected.path());
+        assert_eq!(search.distance_to(Coord::new(2, 0)), None);
+
+        let search = bfs_dense(Coord::ORIGIN, cells.capacity(), index, |coord| get_open(&grid, coord), |_| false);
+
+        assert_eq!(search.distances().len(), grid.values().filter(|c| **c != '#').count());
+    }
+
+    #[test]
+    fn dense_all_paths() {
+        let grid = parse::grid("\
+            ...\n\
+            .#.\n\
+            ...\n\
+        ", Some).unwrap();
+        let end = Coord::new(2, 2);
+        let cells = BitGrid::like(&grid);
+
+        let all = dijkstra_all_dense(
+            Coord::ORIGIN,
+            cells.capacity(),
+            |coord| cells.index(*coord).unwrap(),
+            |coord| get_open(&grid, coord).into_iter().map(|n| (n, 1)).collect::<Vec<_>>(),
+            |coord| *coord == end,
+        );
+
+        assert_eq!(all.distance(), Some(4));
+        assert_eq!(all.goals(), [end]);
+        assert_eq!(all.count(), 2);
+        assert_eq!(all.nodes().len(), 8);
+        assert_eq!(all.paths().len(), 2);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A disjoint-set forest over arbitrary keys, such as grid coordinates. Keys are added as
+/// singletons the first time they are seen.
+#[derive(Clone, Debug, Default)]
+pub struct UnionFind<K> {
+    indices: HashMap<K, usize>,
+    keys: Vec<K>,
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+impl<K: Copy + Eq + Hash> UnionFind<K> {
+    pub fn new() -> UnionFind<K> {
+        UnionFind {
+            indices: HashMap::new(),
+            keys: Vec::new(),
+            parents: Vec::new(),
+            ranks: Vec::new(),
+            sizes: Vec::new(),
+            count: 0,
+        }
+    }
+
+    /// Adds a key in a set of its own, unless it is already present.
+    pub fn insert(&mut self, key: K) {
+        self.index(key);
+    }
+
+    pub fn contains(&self, key: K) -> bool {
+        self.indices.contains_key(&key)
+    }
+
+    /// The representative key of the set containing `key`.
+    pub fn find(&mut self, key: K) -> K {
+        let index = self.index(key);
+        let root = self.root(index);
+        self.keys[root]
+    }
+
+    /// Merges the sets containing `a` and `b`, returning false if they were already joined.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let a = self.index(a);
+        let b = self.index(b);
+        let (a, b) = (self.root(a), self.root(b));
+
+        if a == b {
+            return false;
+        }
+
+        let (parent, child) = if self.ranks[a] < self.ranks[b] { (b, a) } else { (a, b) };
+
+        self.parents[child] = parent;
+        self.sizes[parent] += self.sizes[child];
+        if self.ranks[parent] == self.ranks[child] {
+            self.ranks[parent] += 1;
+        }
+        self.count -= 1;
+
+        true
+    }
+
+    pub fn connected(&mut self, a: K, b: K) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of keys in the set containing `key`.
+    pub fn size(&mut self, key: K) -> usize {
+        let index = self.index(key);
+        let root = self.root(index);
+        self.sizes[root]
+    }
+
+    /// The number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Every set, each listing its keys in the order they were added, ordered by their first key.
+    pub fn components(&mut self) -> Vec<Vec<K>> {
+        let mut positions = HashMap::new();
+        let mut components: Vec<Vec<K>> = Vec::new();
+
+        for index in 0..self.keys.len() {
+            let root = self.root(index);
+            let position = *positions.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[position].push(self.keys[index]);
+        }
+
+        components
+    }
+
+    fn index(&mut self, key: K) -> usize {
+        if let Some(index) = self.indices.get(&key) {
+            return *index;
+        }
+
+        let index = self.keys.len();
+        self.indices.insert(key, index);
+        self.keys.push(key);
+        self.parents.push(index);
+        self.ranks.push(0);
+        self.sizes.push(1);
+        self.count += 1;
+
+        index
+    }
+
+    /// Follows parents up to the root, then points everything on the way directly at it.
+    fn root(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut current = index;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+
+        root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::coord::Coord;
+
+    #[test]
+    fn union() {
+        let mut sets = UnionFind::new();
+
+        assert!(sets.union(1, 2));
+        assert!(sets.union(3, 4));
+        assert!(!sets.union(2, 1));
+        sets.insert(5);
+
+        assert_eq!(sets.len(), 5);
+        assert_eq!(sets.count(), 3);
+        assert!(sets.connected(1, 2));
+        assert!(!sets.connected(2, 3));
+
+        assert!(sets.union(2, 4));
+
+        assert_eq!(sets.count(), 2);
+        assert_eq!(sets.size(1), 4);
+        assert_eq!(sets.find(3), sets.find(2));
+        assert_eq!(sets.components(), vec![vec![1, 2, 3, 4], vec![5]]);
+    }
+
+    #[test]
+    fn coordinates() {
+        let mut sets = UnionFind::new();
+        let row: Vec<Coord> = (0..100).map(|x| Coord::new(x, 0)).collect();
+
+        for pair in row.windows(2) {
+            sets.union(pair[0], pair[1]);
+        }
+
+        assert_eq!(sets.count(), 1);
+        assert_eq!(sets.size(Coord::new(42, 0)), 100);
+        assert!(!sets.contains(Coord::new(0, 1)));
+        assert!(sets.connected(Coord::ORIGIN, Coord::new(99, 0)));
+    }
+}